@@ -37,6 +37,65 @@ def main():
         help='Host to bind to (for HTTP/SSE mode) (default: "127.0.0.1")',
     )
 
+    parser.add_argument(
+        "--confirm",
+        choices=["never", "close", "destructive"],
+        default="close",
+        help="Which destructive tools require user confirmation via MCP elicitation (default: close)",
+    )
+    parser.add_argument(
+        "--elicitation-fallback",
+        choices=["allow", "deny"],
+        default="allow",
+        help="What to do when confirmation is required but the client lacks elicitation support (default: allow)",
+    )
+    parser.add_argument(
+        "--allow-kill",
+        action="store_true",
+        help="Enable the kill_window_process tool",
+    )
+    parser.add_argument(
+        "--allow-input",
+        action="store_true",
+        help="Enable the mouse and keyboard input tools",
+    )
+    parser.add_argument(
+        "--capture-history",
+        type=int,
+        default=32,
+        help="Number of recent captures kept for later tools (default: 32)",
+    )
+    parser.add_argument(
+        "--capture-history-mb",
+        type=int,
+        default=256,
+        help="Memory limit for kept captures in MiB (default: 256)",
+    )
+    parser.add_argument(
+        "--output-dir",
+        default=None,
+        help="Directory captures are saved to when a tool is given a relative save_to path",
+    )
+    parser.add_argument(
+        "--filename-template",
+        default="{date}/{app}-{window_id}-{ts}.png",
+        help="File name template used when save_to names a directory "
+        "(default: {date}/{app}-{window_id}-{ts}.png)",
+    )
+    parser.add_argument(
+        "--write-root",
+        action="append",
+        default=[],
+        dest="write_roots",
+        help="Additional directory save_to may write into (repeatable)",
+    )
+    parser.add_argument(
+        "--image-delivery",
+        choices=["inline", "resource_link"],
+        default="inline",
+        help="How capture tools return images: inline Base64 or a capture:// resource link (default: inline)",
+    )
+
     args = parser.parse_args()
 
     # Import run_server here to avoid issues if the module is not yet compiled
@@ -53,7 +112,22 @@ def main():
 
     # Run the server with the specified options
     try:
-        run_server(sse=args.sse, http=args.http, port=args.port, host=args.host)
+        run_server(
+            sse=args.sse,
+            http=args.http,
+            port=args.port,
+            host=args.host,
+            confirm=args.confirm,
+            elicitation_fallback=args.elicitation_fallback,
+            allow_kill=args.allow_kill,
+            allow_input=args.allow_input,
+            capture_history=args.capture_history,
+            capture_history_mb=args.capture_history_mb,
+            output_dir=args.output_dir,
+            filename_template=args.filename_template,
+            write_roots=args.write_roots,
+            image_delivery=args.image_delivery,
+        )
     except KeyboardInterrupt:
         print("\nServer stopped by user", file=sys.stderr)
         sys.exit(130)  # Standard exit code for SIGINT
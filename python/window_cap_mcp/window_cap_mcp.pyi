@@ -102,6 +102,16 @@ def run_server(
     http: bool = False,
     port: int = 8080,
     host: str = "127.0.0.1",
+    confirm: str = "close",
+    elicitation_fallback: str = "allow",
+    allow_kill: bool = False,
+    allow_input: bool = False,
+    capture_history: int = 32,
+    capture_history_mb: int = 256,
+    output_dir: Optional[str] = None,
+    filename_template: str = "{date}/{app}-{window_id}-{ts}.png",
+    write_roots: List[str] = [],
+    image_delivery: str = "inline",
 ) -> None:
     """
     Run the MCP server
@@ -111,6 +121,18 @@ def run_server(
         http: Use Streamable HTTP protocol
         port: Port to listen on (for HTTP/SSE mode)
         host: Host to bind to (for HTTP/SSE mode)
+        confirm: Which destructive tools require user confirmation
+            ("never", "close" or "destructive")
+        elicitation_fallback: What to do when confirmation is required but the
+            client lacks elicitation support ("allow" or "deny")
+        allow_kill: Enable the kill_window_process tool
+        allow_input: Enable the mouse and keyboard input tools
+        capture_history: Number of recent captures kept for later tools
+        capture_history_mb: Memory limit for kept captures in MiB
+        output_dir: Directory captures are saved to for relative save_to paths
+        filename_template: File name template used when save_to names a directory
+        write_roots: Additional directories save_to may write into
+        image_delivery: How capture tools return images ("inline" or "resource_link")
 
     Examples:
         >>> import window_cap_mcp as wc
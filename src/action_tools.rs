@@ -0,0 +1,266 @@
+use crate::config::ImageDelivery;
+use crate::handler::{input_disabled, store_capture, CaptureOptions, WindowCapServer};
+use crate::models::*;
+use crate::utils::capture;
+use crate::utils::capture_store::SessionCaptures;
+use crate::utils::output::OutputConfig;
+use crate::utils::window_ops::{self, WindowAction, WindowMatcher};
+#[cfg(feature = "input")]
+use crate::{input_tools::focus_target, utils::input_ops};
+use rmcp::{
+    handler::server::wrapper::Parameters, model::*, service::RequestContext, tool, tool_router,
+    ErrorData as McpError, RoleServer,
+};
+use std::time::{Duration, Instant};
+
+/// Longest pause a `wait` step may ask for, and longest `wait_for_window` timeout
+pub const MAX_STEP_WAIT_MS: u64 = 60_000;
+
+/// Interval at which a `wait` step checks whether the request was cancelled
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[tool_router(router = action_tool_router, vis = "pub(crate)")]
+impl WindowCapServer {
+    #[tool(
+        description = "Run a sequence of steps (focus_window, click, type, press_keys, wait, wait_for_window, capture_window, capture_region) server-side in one call. Stops at the first failing step and returns per-step results plus any captured images",
+        annotations(read_only_hint = false)
+    )]
+    async fn run_actions(
+        &self,
+        params: Parameters<RunActionsParams>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let steps = params.0.steps;
+        if steps.is_empty() {
+            return Err(McpError::invalid_params("No steps given", None));
+        }
+        for (index, step) in steps.iter().enumerate() {
+            validate_step(step)
+                .map_err(|e| McpError::invalid_params(format!("Step {}: {}", index, e), None))?;
+        }
+
+        let needs_input = steps.iter().any(|step| {
+            matches!(
+                step,
+                ActionStep::Click { .. } | ActionStep::Type { .. } | ActionStep::PressKeys { .. }
+            )
+        });
+        if needs_input && !self.config.allow_input {
+            return Ok(input_disabled());
+        }
+
+        let captures = self.captures.clone();
+        let output = self.config.output.clone();
+        let delivery = self.config.image_delivery;
+        let ct = context.ct.clone();
+        let (results, images, failed) = tokio::task::spawn_blocking(move || {
+            run_steps(&captures, &output, delivery, steps, &|| ct.is_cancelled())
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?;
+
+        let summary = serde_json::to_string_pretty(&serde_json::json!({
+            "completed": !failed,
+            "steps": results,
+        }))
+        .map_err(|e| McpError::internal_error(format!("JSON serialization failed: {}", e), None))?;
+
+        let mut content = vec![Content::text(summary)];
+        content.extend(images);
+
+        Ok(if failed {
+            CallToolResult::error(content)
+        } else {
+            CallToolResult::success(content)
+        })
+    }
+}
+
+/// Check a step's parameters before any step runs
+fn validate_step(step: &ActionStep) -> Result<(), String> {
+    match step {
+        ActionStep::Click { .. } | ActionStep::Type { .. } | ActionStep::PressKeys { .. }
+            if cfg!(not(feature = "input")) =>
+        {
+            Err(
+                "Input steps are unavailable; the server was built without the input feature"
+                    .to_string(),
+            )
+        }
+        ActionStep::Wait { ms } if *ms > MAX_STEP_WAIT_MS => {
+            Err(format!("wait ms must be at most {}", MAX_STEP_WAIT_MS))
+        }
+        ActionStep::WaitForWindow {
+            title: None,
+            app_name: None,
+            ..
+        } => Err("wait_for_window needs a title and/or app_name to match".to_string()),
+        ActionStep::WaitForWindow {
+            timeout_ms: Some(timeout_ms),
+            ..
+        } if *timeout_ms > MAX_STEP_WAIT_MS => Err(format!(
+            "wait_for_window timeout_ms must be at most {}",
+            MAX_STEP_WAIT_MS
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Execute macro steps in order, stopping at the first failure or on cancellation
+///
+/// Captures are kept in `captures` like any other screenshot. `is_cancelled` is checked
+/// before every step and during waits. Returns the per-step results, the images (or
+/// resource links) captured along the way and whether a step failed or was cancelled.
+fn run_steps(
+    captures: &SessionCaptures,
+    output: &OutputConfig,
+    delivery: ImageDelivery,
+    steps: Vec<ActionStep>,
+    is_cancelled: &dyn Fn() -> bool,
+) -> (Vec<serde_json::Value>, Vec<Content>, bool) {
+    let mut results = Vec::new();
+    let mut images = Vec::new();
+
+    for (index, step) in steps.into_iter().enumerate() {
+        let action = serde_json::to_value(&step)
+            .ok()
+            .and_then(|v| v.get("action").cloned())
+            .unwrap_or_default();
+
+        let outcome = if is_cancelled() {
+            Err("Cancelled before this step ran".to_string())
+        } else {
+            run_step(captures, output, delivery, step, is_cancelled)
+        };
+        match outcome {
+            Ok((message, image)) => {
+                let mut result = serde_json::json!({
+                    "step": index,
+                    "action": action,
+                    "ok": true,
+                    "message": message,
+                });
+                if let Some(image) = image {
+                    result["image_index"] = images.len().into();
+                    images.push(image);
+                }
+                results.push(result);
+            }
+            Err(error) => {
+                results.push(serde_json::json!({
+                    "step": index,
+                    "action": action,
+                    "ok": false,
+                    "error": error,
+                }));
+                return (results, images, true);
+            }
+        }
+    }
+
+    (results, images, false)
+}
+
+/// Execute a single macro step, returning a message and an optional image
+fn run_step(
+    captures: &SessionCaptures,
+    output: &OutputConfig,
+    delivery: ImageDelivery,
+    step: ActionStep,
+    is_cancelled: &dyn Fn() -> bool,
+) -> Result<(String, Option<Content>), String> {
+    match step {
+        ActionStep::FocusWindow { window_id } => {
+            let message = window_ops::manage_window_with_info(window_id, WindowAction::Focus)?;
+            Ok((message, None))
+        }
+        ActionStep::Click { .. } | ActionStep::Type { .. } | ActionStep::PressKeys { .. } => {
+            run_input_step(step)
+        }
+        ActionStep::Wait { ms } => {
+            let deadline = Instant::now() + Duration::from_millis(ms);
+            while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+                if is_cancelled() {
+                    return Err("Cancelled while waiting".to_string());
+                }
+                std::thread::sleep(left.min(CANCEL_POLL_INTERVAL));
+            }
+            Ok((format!("Waited {} ms", ms), None))
+        }
+        ActionStep::WaitForWindow {
+            title,
+            app_name,
+            timeout_ms,
+        } => {
+            let matcher = WindowMatcher { title, app_name };
+            let timeout = Duration::from_millis(timeout_ms.unwrap_or(5000));
+            let window = window_ops::wait_for_window(&matcher, timeout)?;
+            Ok((
+                format!(
+                    "Found window: {} [{}] (ID: {})",
+                    window.title().unwrap_or_default(),
+                    window.app_name().unwrap_or_default(),
+                    window.id().unwrap_or(0)
+                ),
+                None,
+            ))
+        }
+        ActionStep::CaptureWindow { window_id, save_to } => {
+            let capture = capture::capture_window(window_id)?;
+            let options = CaptureOptions::new(save_to, delivery);
+            let (metadata, image) = store_capture(captures, output, capture, &options)?;
+            Ok((metadata, Some(image)))
+        }
+        ActionStep::CaptureRegion {
+            x,
+            y,
+            width,
+            height,
+            save_to,
+        } => {
+            let capture = capture::capture_region(x, y, width, height)?;
+            let options = CaptureOptions::new(save_to, delivery);
+            let (metadata, image) = store_capture(captures, output, capture, &options)?;
+            Ok((metadata, Some(image)))
+        }
+    }
+}
+
+/// Execute a click, type or press_keys step
+#[cfg(feature = "input")]
+fn run_input_step(step: ActionStep) -> Result<(String, Option<Content>), String> {
+    match step {
+        ActionStep::Click {
+            x,
+            y,
+            window_id,
+            button,
+            count,
+        } => {
+            let (x, y) = input_ops::resolve_point(x, y, window_id)?;
+            let count = count.unwrap_or(1);
+            input_ops::mouse_click(Some((x, y)), button.unwrap_or_default().number(), count)?;
+            Ok((format!("Clicked {} time(s) at ({}, {})", count, x, y), None))
+        }
+        ActionStep::Type {
+            text,
+            window_id,
+            delay_ms,
+        } => {
+            focus_target(window_id)?;
+            input_ops::type_text(&text, Duration::from_millis(delay_ms.unwrap_or(10)))?;
+            Ok((format!("Typed {} character(s)", text.chars().count()), None))
+        }
+        ActionStep::PressKeys { keys, window_id } => {
+            focus_target(window_id)?;
+            input_ops::press_keys(&keys)?;
+            Ok((format!("Pressed {}", keys), None))
+        }
+        _ => Err("Not an input step".to_string()),
+    }
+}
+
+#[cfg(not(feature = "input"))]
+fn run_input_step(_step: ActionStep) -> Result<(String, Option<Content>), String> {
+    Err("Input steps are unavailable; the server was built without the input feature".to_string())
+}
@@ -0,0 +1,72 @@
+use bytes::Bytes;
+use http_body_util::{combinators::BoxBody, BodyExt, Full};
+use hyper::{header, Method, Request, Response, StatusCode};
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use crate::utils::capture_store::CaptureStore;
+
+/// Path prefix under which stored captures are served in HTTP mode
+pub const CAPTURES_PATH: &str = "/captures/";
+
+/// Serve `GET /captures/{id}/{token}` from the capture store, as PNG or as the animation
+/// of a recording
+///
+/// The token is the secret listed with the capture by `list_captures`, so a capture can
+/// only be fetched by the session that took it, or whoever it shared the path with.
+/// Returns `None` for every other request, which should be passed on to the MCP service.
+pub async fn serve_capture<B>(
+    captures: &Arc<CaptureStore>,
+    request: &Request<B>,
+) -> Option<Response<BoxBody<Bytes, Infallible>>> {
+    let path = request.uri().path().strip_prefix(CAPTURES_PATH)?;
+    if request.method() != Method::GET {
+        return Some(plain_response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "Only GET is supported for captures".to_string(),
+        ));
+    }
+
+    // Unknown IDs and wrong tokens get the same answer, so IDs cannot be probed
+    let capture = path
+        .split_once('/')
+        .and_then(|(id, token)| captures.get_with_token(id.parse().ok()?, token));
+    let capture = match capture {
+        Some(capture) => capture,
+        None => {
+            return Some(plain_response(
+                StatusCode::NOT_FOUND,
+                "Capture does not exist, has expired or the token is wrong".to_string(),
+            ))
+        }
+    };
+
+    let mime_type = capture.mime_type();
+    let data = tokio::task::spawn_blocking(move || capture.encoded().map(|data| data.into_owned()))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+        .and_then(|result| result);
+    Some(match data {
+        Ok(data) => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, mime_type)
+            // A capture never changes once stored
+            .header(
+                header::CACHE_CONTROL,
+                "private, max-age=31536000, immutable",
+            )
+            .body(Full::new(Bytes::from(data)).boxed())
+            .unwrap_or_else(|e| plain_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        Err(e) => plain_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    })
+}
+
+fn plain_response(status: StatusCode, message: String) -> Response<BoxBody<Bytes, Infallible>> {
+    let mut response = Response::new(Full::new(Bytes::from(message)).boxed());
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("text/plain; charset=utf-8"),
+    );
+    response
+}
@@ -1,4 +1,8 @@
+use crate::config::{ConfirmPolicy, ElicitationFallback, ImageDelivery, ServerConfig};
+use crate::utils::capture_store::{CaptureLimits, DEFAULT_MAX_CAPTURES, DEFAULT_MAX_MEGABYTES};
+use crate::utils::output::{OutputConfig, DEFAULT_FILENAME_TEMPLATE};
 use clap::Parser;
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(name = "window-cap-mcp")]
@@ -19,4 +23,66 @@ pub struct Cli {
     /// Host to bind to (for HTTP/SSE mode)
     #[arg(long, default_value = "127.0.0.1")]
     pub host: String,
+
+    /// Which destructive tools require user confirmation via MCP elicitation
+    #[arg(long, value_enum, default_value_t = ConfirmPolicy::Close)]
+    pub confirm: ConfirmPolicy,
+
+    /// What to do when confirmation is required but the client lacks elicitation support
+    #[arg(long, value_enum, default_value_t = ElicitationFallback::Allow)]
+    pub elicitation_fallback: ElicitationFallback,
+
+    /// Enable the kill_window_process tool
+    #[arg(long)]
+    pub allow_kill: bool,
+
+    /// Enable the mouse and keyboard input tools
+    #[arg(long)]
+    pub allow_input: bool,
+
+    /// Number of recent captures kept for list_captures, get_capture and diff_screenshots
+    #[arg(long, default_value_t = DEFAULT_MAX_CAPTURES)]
+    pub capture_history: usize,
+
+    /// Memory limit for kept captures in MiB of decoded image data
+    #[arg(long, default_value_t = DEFAULT_MAX_MEGABYTES)]
+    pub capture_history_mb: usize,
+
+    /// Directory captures are saved to when a tool is given a relative save_to path
+    #[arg(long)]
+    pub output_dir: Option<PathBuf>,
+
+    /// File name template used when save_to names a directory
+    #[arg(long, default_value = DEFAULT_FILENAME_TEMPLATE)]
+    pub filename_template: String,
+
+    /// Additional directory save_to may write into (repeatable)
+    #[arg(long = "write-root")]
+    pub write_roots: Vec<PathBuf>,
+
+    /// How capture tools return images by default: inline Base64 or a capture:// resource link
+    #[arg(long, value_enum, default_value_t = ImageDelivery::Inline)]
+    pub image_delivery: ImageDelivery,
+}
+
+impl Cli {
+    /// Build the server configuration from the parsed arguments
+    pub fn server_config(&self) -> ServerConfig {
+        ServerConfig {
+            confirm: self.confirm,
+            elicitation_fallback: self.elicitation_fallback,
+            allow_kill: self.allow_kill,
+            allow_input: self.allow_input,
+            capture_limits: CaptureLimits {
+                max_captures: self.capture_history,
+                max_bytes: self.capture_history_mb * 1024 * 1024,
+            },
+            output: OutputConfig {
+                dir: self.output_dir.clone(),
+                filename_template: self.filename_template.clone(),
+                write_roots: self.write_roots.clone(),
+            },
+            image_delivery: self.image_delivery,
+        }
+    }
 }
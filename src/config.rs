@@ -0,0 +1,82 @@
+use clap::ValueEnum;
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::capture_store::CaptureLimits;
+use crate::utils::output::OutputConfig;
+
+/// Which destructive tools must be confirmed by the user before they run
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ConfirmPolicy {
+    /// Never ask for confirmation
+    Never,
+    /// Ask before closing a window
+    #[default]
+    Close,
+    /// Ask before every destructive tool
+    Destructive,
+}
+
+impl ConfirmPolicy {
+    /// Whether `close_window` must be confirmed
+    pub fn confirms_close(self) -> bool {
+        self != ConfirmPolicy::Never
+    }
+
+    /// Whether destructive tools other than `close_window` must be confirmed
+    pub fn confirms_destructive(self) -> bool {
+        self == ConfirmPolicy::Destructive
+    }
+}
+
+/// What to do when confirmation is required but the client does not support elicitation
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ElicitationFallback {
+    /// Run the tool without confirmation
+    #[default]
+    Allow,
+    /// Refuse to run the tool
+    Deny,
+}
+
+/// How capture tools hand images to the client
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    ValueEnum,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum ImageDelivery {
+    /// Embed the image as Base64 PNG content
+    #[default]
+    Inline,
+    /// Return a `capture://{id}` resource link the client can read when needed
+    ResourceLink,
+}
+
+/// Runtime configuration shared by every server instance
+#[derive(Clone, Debug, Default)]
+pub struct ServerConfig {
+    /// Which destructive tools require user confirmation
+    pub confirm: ConfirmPolicy,
+    /// Policy applied when the client cannot be asked for confirmation
+    pub elicitation_fallback: ElicitationFallback,
+    /// Whether `kill_window_process` may terminate processes
+    pub allow_kill: bool,
+    /// Whether the mouse and keyboard input tools may synthesize events
+    pub allow_input: bool,
+    /// How many recent captures are kept for later tools to refer to
+    pub capture_limits: CaptureLimits,
+    /// Where captures may be saved to disk
+    pub output: OutputConfig,
+    /// Default for how capture tools return images
+    pub image_delivery: ImageDelivery,
+}
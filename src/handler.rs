@@ -1,14 +1,29 @@
+use crate::config::{ElicitationFallback, ImageDelivery, ServerConfig};
 use crate::models::*;
-use crate::utils::window_ops;
+use crate::utils::annotate::{self, Annotation, DEFAULT_GRID_SPACING};
+use crate::utils::capture::{self, Capture, CaptureSource};
+use crate::utils::capture_store::{self, CaptureStore, SessionCaptures};
+use crate::utils::change_wait::{self, WaitOptions};
+use crate::utils::coordinates;
+use crate::utils::cursor;
+use crate::utils::output::{self, OutputConfig};
+use crate::utils::recording::{self, Recording};
+use crate::utils::window_ops::{self, WindowAction, WindowMatcher, WindowState, WindowType};
+use crate::utils::{image_diff, monitor_ops, process_ops, thumbnails};
 use rmcp::{
-    handler::server::wrapper::Parameters, model::*, tool, tool_handler, tool_router,
-    ErrorData as McpError, ServerHandler,
+    handler::server::wrapper::Parameters, model::*, service::ElicitationError,
+    service::RequestContext, tool, tool_handler, tool_router, ErrorData as McpError, Peer,
+    RoleServer, ServerHandler,
 };
-use xcap::{Monitor, Window};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 #[derive(Clone)]
 pub struct WindowCapServer {
     pub tool_router: rmcp::handler::server::tool::ToolRouter<Self>,
+    pub config: ServerConfig,
+    /// Recent captures of this session, in a store shared by every session of the server
+    pub captures: SessionCaptures,
 }
 
 impl Default for WindowCapServer {
@@ -20,35 +35,41 @@ impl Default for WindowCapServer {
 #[tool_router]
 impl WindowCapServer {
     pub fn new() -> Self {
+        Self::with_config(ServerConfig::default())
+    }
+
+    pub fn with_config(config: ServerConfig) -> Self {
+        #[allow(unused_mut)]
+        let mut tool_router = Self::tool_router() + Self::action_tool_router();
+        #[cfg(feature = "input")]
+        {
+            tool_router += Self::input_tool_router();
+        }
+
+        let captures = Arc::new(CaptureStore::new(config.capture_limits)).session();
         Self {
-            tool_router: Self::tool_router(),
+            tool_router,
+            config,
+            captures,
         }
     }
 
-    #[tool(description = "Get the number and details of monitors")]
+    /// Use a capture store shared with other server instances, as a new session of it
+    pub fn with_capture_store(mut self, captures: Arc<CaptureStore>) -> Self {
+        self.captures = captures.session();
+        self
+    }
+
+    #[tool(
+        description = "Get the number and details of monitors, including scale factor, rotation, refresh rate, physical size, DPI and work area"
+    )]
     async fn get_monitor_count(
         &self,
         _params: Parameters<EmptyParams>,
     ) -> Result<CallToolResult, McpError> {
         // 在阻塞线程中执行获取显示器信息的操作
         let result = tokio::task::spawn_blocking(move || -> Result<String, String> {
-            let monitors = Monitor::all().map_err(|e| format!("Failed to get monitors: {}", e))?;
-
-            let monitor_list: Vec<_> = monitors
-                .iter()
-                .enumerate()
-                .map(|(idx, m)| {
-                    serde_json::json!({
-                        "index": idx,
-                        "name": m.name().unwrap_or_default(),
-                        "x": m.x().unwrap_or(0),
-                        "y": m.y().unwrap_or(0),
-                        "width": m.width().unwrap_or(0),
-                        "height": m.height().unwrap_or(0),
-                        "is_primary": m.is_primary().unwrap_or(false),
-                    })
-                })
-                .collect();
+            let monitor_list = monitor_ops::list_monitors()?;
 
             let result = serde_json::json!({
                 "count": monitor_list.len(),
@@ -70,95 +91,180 @@ impl WindowCapServer {
         &self,
         params: Parameters<ScreenshotParams>,
     ) -> Result<CallToolResult, McpError> {
-        let monitor_index = params.0.monitor_index.map(|idx| idx as usize);
+        let params = params.0;
+        let monitor_index = params.monitor_index.map(|idx| idx as usize);
+        let options = self
+            .capture_options(params.save_to, params.delivery)
+            .annotated(params.annotate, params.grid_spacing);
+        let include_cursor = params.include_cursor.unwrap_or(false);
+        self.run_capture(options, move || {
+            with_cursor(capture::capture_monitor(monitor_index)?, include_cursor)
+        })
+        .await
+    }
 
-        // 在阻塞线程中执行耗时的截图和编码操作
-        let result = tokio::task::spawn_blocking(move || -> Result<(String, String), String> {
-            let monitors = Monitor::all().map_err(|e| format!("Failed to get monitors: {}", e))?;
+    #[tool(
+        description = "Get a list of windows, top-most first, with z-order, owner (transient_for), visibility and window type. Only normal and dialog windows are listed unless window_types is given"
+    )]
+    async fn get_window_list(
+        &self,
+        params: Parameters<WindowListParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let include_process = params.0.include_process.unwrap_or(false);
+        let window_types = params
+            .0
+            .window_types
+            .unwrap_or_else(|| WindowType::DEFAULT_FILTER.to_vec());
 
-            if monitors.is_empty() {
-                return Err("No monitors available".to_string());
+        // 在阻塞线程中执行获取窗口列表的操作
+        let result = tokio::task::spawn_blocking(move || -> Result<String, String> {
+            let mut window_list = window_ops::list_windows()?;
+            window_list.retain(|info| {
+                window_types.contains(&info.window_type.unwrap_or(WindowType::Normal))
+            });
+            if include_process {
+                window_ops::attach_processes(&mut window_list)?;
             }
 
-            let monitor = if let Some(idx) = monitor_index {
-                monitors
-                    .get(idx)
-                    .ok_or_else(|| format!("Monitor index {} does not exist", idx))?
-            } else {
-                monitors
-                    .iter()
-                    .find(|m| m.is_primary().unwrap_or(false))
-                    .or_else(|| monitors.first())
-                    .ok_or_else(|| "Unable to find primary monitor".to_string())?
-            };
+            let result = serde_json::json!({
+                "count": window_list.len(),
+                "windows": window_list
+            });
 
-            let image = monitor
-                .capture_image()
-                .map_err(|e| format!("Screenshot failed: {}", e))?;
+            serde_json::to_string_pretty(&result)
+                .map_err(|e| format!("JSON serialization failed: {}", e))
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+        .map_err(|e| McpError::internal_error(e, None))?;
 
-            let mut buffer = Vec::new();
-            image
-                .write_to(
-                    &mut std::io::Cursor::new(&mut buffer),
-                    image::ImageFormat::Png,
-                )
-                .map_err(|e| format!("Image encoding failed: {}", e))?;
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "Get the full record of a window, including stacking, visibility and its owning process (PID, executable, command line, parent PID, memory)"
+    )]
+    async fn get_window_details(
+        &self,
+        params: Parameters<WindowParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let window_id = params.0.window_id;
 
-            let base64_image =
-                base64::Engine::encode(&base64::engine::general_purpose::STANDARD, buffer);
+        let result = tokio::task::spawn_blocking(move || -> Result<String, String> {
+            let details = window_ops::window_details(window_id)?;
+            serde_json::to_string_pretty(&details)
+                .map_err(|e| format!("JSON serialization failed: {}", e))
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+        .map_err(|e| McpError::internal_error(e, None))?;
 
-            let metadata = format!(
-                "Monitor: {} (Index: {}, Size: {}x{})",
-                monitor.name().unwrap_or_default(),
-                monitor_index.unwrap_or(0),
-                monitor.width().unwrap_or(0),
-                monitor.height().unwrap_or(0)
-            );
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
 
-            Ok((metadata, base64_image))
+    #[tool(
+        description = "Read raw X11 properties of a window like xprop: WM_CLASS, WM_NAME, _NET_WM_NAME, _NET_WM_WINDOW_TYPE, _NET_WM_STATE, _NET_WM_DESKTOP, WM_CLIENT_MACHINE and any additional requested atoms (Linux only)"
+    )]
+    async fn get_window_properties(
+        &self,
+        params: Parameters<WindowPropertiesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let window_id = params.0.window_id;
+        let atoms = params.0.atoms.unwrap_or_default();
+
+        let result = tokio::task::spawn_blocking(move || -> Result<String, String> {
+            let properties = window_ops::window_properties(window_id, &atoms)?;
+            serde_json::to_string_pretty(&properties)
+                .map_err(|e| format!("JSON serialization failed: {}", e))
         })
         .await
         .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
         .map_err(|e| McpError::internal_error(e, None))?;
 
-        Ok(CallToolResult::success(vec![
-            Content::text(result.0),
-            Content::image(result.1, "image/png".to_string()),
-        ]))
+        Ok(CallToolResult::success(vec![Content::text(result)]))
     }
 
-    #[tool(description = "Get a list of all windows")]
-    async fn get_window_list(
+    #[tool(
+        description = "Convert a point between desktop coordinates and screenshot pixels of a monitor or window, accounting for monitor offsets, scale factor and capture resizing"
+    )]
+    async fn transform_coordinates(
+        &self,
+        params: Parameters<TransformCoordinatesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        let from = params
+            .from
+            .space_ref()
+            .map_err(|e| McpError::invalid_params(format!("from: {}", e), None))?;
+        let to = params
+            .to
+            .space_ref()
+            .map_err(|e| McpError::invalid_params(format!("to: {}", e), None))?;
+
+        let result = tokio::task::spawn_blocking(move || -> Result<String, String> {
+            let point = coordinates::transform_point(params.x, params.y, from, to)?;
+            serde_json::to_string_pretty(&point)
+                .map_err(|e| format!("JSON serialization failed: {}", e))
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+        .map_err(|e| McpError::internal_error(e, None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(description = "Get the window that currently has keyboard focus")]
+    async fn get_active_window(
         &self,
         _params: Parameters<EmptyParams>,
     ) -> Result<CallToolResult, McpError> {
-        // 在阻塞线程中执行获取窗口列表的操作
         let result = tokio::task::spawn_blocking(move || -> Result<String, String> {
-            let windows = Window::all().map_err(|e| format!("Failed to get window list: {}", e))?;
-
-            let window_list: Vec<_> = windows
-                .iter()
-                .map(|w| {
-                    serde_json::json!({
-                        "id": w.id().unwrap_or(0),
-                        "title": w.title().unwrap_or_default(),
-                        "app_name": w.app_name().unwrap_or_default(),
-                        "x": w.x().unwrap_or(0),
-                        "y": w.y().unwrap_or(0),
-                        "width": w.width().unwrap_or(0),
-                        "height": w.height().unwrap_or(0),
-                        "is_minimized": w.is_minimized().unwrap_or(false),
-                        "is_maximized": w.is_maximized().unwrap_or(false),
-                    })
-                })
-                .collect();
+            match window_ops::active_window_info()? {
+                Some(info) => serde_json::to_string_pretty(&info)
+                    .map_err(|e| format!("JSON serialization failed: {}", e)),
+                None => Ok("No window is active".to_string()),
+            }
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+        .map_err(|e| McpError::internal_error(e, None))?;
 
-            let result = serde_json::json!({
-                "count": window_list.len(),
-                "windows": window_list
-            });
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
 
-            serde_json::to_string_pretty(&result)
+    #[tool(
+        description = "Get the top-most visible window containing a point in desktop coordinates"
+    )]
+    async fn get_window_at_point(
+        &self,
+        params: Parameters<PointParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let (x, y) = (params.0.x, params.0.y);
+
+        let result = tokio::task::spawn_blocking(move || -> Result<String, String> {
+            match window_ops::window_at_point(x, y)? {
+                Some(info) => serde_json::to_string_pretty(&info)
+                    .map_err(|e| format!("JSON serialization failed: {}", e)),
+                None => Ok(format!("No window found at ({}, {})", x, y)),
+            }
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+        .map_err(|e| McpError::internal_error(e, None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "Get the mouse pointer position in desktop coordinates, with the monitor and top-most window under it and the buttons held down (Linux only)"
+    )]
+    async fn get_cursor_position(
+        &self,
+        _params: Parameters<EmptyParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let result = tokio::task::spawn_blocking(move || -> Result<String, String> {
+            let position = cursor::cursor_position()?;
+            serde_json::to_string_pretty(&position)
                 .map_err(|e| format!("JSON serialization failed: {}", e))
         })
         .await
@@ -168,82 +274,945 @@ impl WindowCapServer {
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
 
+    #[tool(
+        description = "Capture every visible window as a thumbnail and return one labelled grid image (cell number, window ID, app name, title) plus a JSON index mapping grid cells to window IDs. Use it to pick a window without capturing them one by one"
+    )]
+    async fn get_window_thumbnails(
+        &self,
+        params: Parameters<WindowThumbnailsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        let window_types = params
+            .window_types
+            .unwrap_or_else(|| WindowType::DEFAULT_FILTER.to_vec());
+        let options = thumbnails::SheetOptions {
+            thumbnail_width: params.thumbnail_width.unwrap_or(320).clamp(64, 1024),
+            thumbnail_height: params.thumbnail_height.unwrap_or(200).clamp(64, 1024),
+            columns: params.columns,
+        };
+        let capture_options = self.capture_options(params.save_to, params.delivery);
+        let captures = self.captures.clone();
+        let output = self.config.output.clone();
+
+        let (index, metadata, image) =
+            tokio::task::spawn_blocking(move || -> Result<(String, String, Content), String> {
+                let mut windows = window_ops::list_windows()?;
+                windows.retain(|info| {
+                    info.is_visible()
+                        && info.width > 0
+                        && info.height > 0
+                        && window_types.contains(&info.window_type.unwrap_or(WindowType::Normal))
+                });
+                let omitted = windows.len().saturating_sub(MAX_THUMBNAILS);
+                windows.truncate(MAX_THUMBNAILS);
+
+                let sheet = thumbnails::build_contact_sheet(&windows, options)?;
+                let index = serde_json::to_string_pretty(&serde_json::json!({
+                    "count": sheet.cells.len(),
+                    "columns": sheet.columns,
+                    "rows": sheet.rows,
+                    "width": sheet.image.width(),
+                    "height": sheet.image.height(),
+                    "cells": sheet.cells,
+                    "skipped": sheet.skipped,
+                    "omitted": omitted,
+                }))
+                .map_err(|e| format!("JSON serialization failed: {}", e))?;
+
+                let description = format!(
+                    "Window thumbnails: {} window(s) (Size: {}x{})",
+                    sheet.cells.len(),
+                    sheet.image.width(),
+                    sheet.image.height()
+                );
+                let capture =
+                    Capture::composed(CaptureSource::ContactSheet, description, sheet.image);
+                let (metadata, image) =
+                    store_capture(&captures, &output, capture, &capture_options)?;
+                Ok((index, metadata, image))
+            })
+            .await
+            .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+            .map_err(|e| McpError::internal_error(e, None))?;
+
+        Ok(CallToolResult::success(vec![
+            Content::text(index),
+            Content::text(metadata),
+            image,
+        ]))
+    }
+
     #[tool(description = "Get a screenshot of the specified window")]
     async fn get_window_screenshot(
         &self,
         params: Parameters<WindowScreenshotParams>,
     ) -> Result<CallToolResult, McpError> {
-        let window_id = params.0.window_id;
+        let params = params.0;
+        let window_id = params.window_id;
+        let options = self
+            .capture_options(params.save_to, params.delivery)
+            .annotated(params.annotate, params.grid_spacing);
+        let include_cursor = params.include_cursor.unwrap_or(false);
+        self.run_capture(options, move || {
+            with_cursor(capture::capture_window(window_id)?, include_cursor)
+        })
+        .await
+    }
 
-        // 在阻塞线程中执行耗时的截图和编码操作
-        let result = tokio::task::spawn_blocking(move || -> Result<(String, String), String> {
-            let windows = Window::all().map_err(|e| format!("Failed to get window list: {}", e))?;
-
-            let window = windows
-                .iter()
-                .find(|w| w.id().unwrap_or(0) == window_id)
-                .ok_or_else(|| format!("Window ID {} does not exist", window_id))?;
-
-            let image = window
-                .capture_image()
-                .map_err(|e| format!("Window screenshot failed: {}", e))?;
-
-            let mut buffer = Vec::new();
-            image
-                .write_to(
-                    &mut std::io::Cursor::new(&mut buffer),
-                    image::ImageFormat::Png,
-                )
-                .map_err(|e| format!("Image encoding failed: {}", e))?;
+    #[tool(
+        description = "Capture the source of an earlier screenshot again (or use a second stored capture) and report which regions changed, with bounding boxes and the share of changed pixels. Optionally returns an image highlighting the changes"
+    )]
+    async fn diff_screenshots(
+        &self,
+        params: Parameters<DiffScreenshotsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        let threshold = params.pixel_threshold.unwrap_or(DEFAULT_PIXEL_THRESHOLD);
+        let include_image = params.include_image.unwrap_or(false);
+        let delivery = params.delivery.unwrap_or(self.config.image_delivery);
+        let captures = self.captures.clone();
+
+        let (summary, highlight) =
+            tokio::task::spawn_blocking(move || -> Result<(String, Option<Content>), String> {
+                let baseline = captures.require(params.capture_id)?;
+                let (current_id, current) = match params.compare_to {
+                    Some(id) => (id, captures.require(id)?),
+                    None => {
+                        let capture = Arc::new(baseline.source.capture()?);
+                        (captures.insert(capture.clone()), capture)
+                    }
+                };
+
+                // Cursors and annotations drawn on either capture are not changes on screen
+                let report = image_diff::diff_images(baseline.raw(), current.raw(), threshold)?;
+                let highlight = if include_image {
+                    let image = image_diff::highlight_changes(current.raw(), &report);
+                    let highlighted = Arc::new(current.derived(
+                        image,
+                        &format!("Changes since capture {} highlighted", params.capture_id),
+                    ));
+                    let id = captures.insert(highlighted.clone());
+                    Some((id, deliver_capture(id, &highlighted, delivery)?))
+                } else {
+                    None
+                };
+
+                let summary = serde_json::to_string_pretty(&serde_json::json!({
+                    "baseline_id": params.capture_id,
+                    "capture_id": current_id,
+                    "highlight_id": highlight.as_ref().map(|(id, _)| *id),
+                    "highlight_url": highlight.as_ref().and_then(|(id, _)| captures.http_url(*id)),
+                    "changed": !report.is_unchanged(),
+                    "diff": report,
+                }))
+                .map_err(|e| format!("JSON serialization failed: {}", e))?;
+                Ok((summary, highlight.map(|(_, content)| content)))
+            })
+            .await
+            .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+            .map_err(|e| McpError::internal_error(e, None))?;
+
+        let mut content = vec![Content::text(summary)];
+        content.extend(highlight);
+        Ok(CallToolResult::success(content))
+    }
+
+    #[tool(
+        description = "List the captures kept by the server, newest first, with ID, timestamp, source, size and metadata. The IDs can be passed to get_capture and diff_screenshots"
+    )]
+    async fn list_captures(
+        &self,
+        _params: Parameters<EmptyParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let (count, bytes) = self.captures.usage();
+        let limits = self.captures.limits();
+        let result = serde_json::to_string_pretty(&serde_json::json!({
+            "count": count,
+            "bytes": bytes,
+            "max_captures": limits.max_captures,
+            "max_bytes": limits.max_bytes,
+            "captures": self.captures.list(),
+        }))
+        .map_err(|e| McpError::internal_error(format!("JSON serialization failed: {}", e), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "Get a stored capture again by ID, optionally as JPEG and/or scaled down, without taking a new screenshot"
+    )]
+    async fn get_capture(
+        &self,
+        params: Parameters<GetCaptureParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        let encoding = params.format.unwrap_or_default();
+        let scale = params.scale.unwrap_or(1.0);
+        if !(scale > 0.0 && scale <= 1.0) {
+            return Err(McpError::invalid_params(
+                "scale must be greater than 0 and at most 1",
+                None,
+            ));
+        }
+        let captures = self.captures.clone();
+        let output = self.config.output.clone();
+
+        let (metadata, image) =
+            tokio::task::spawn_blocking(move || -> Result<(String, String), String> {
+                let capture = captures.require(params.capture_id)?;
+                let mut metadata =
+                    format!("{}\nCapture ID: {}", capture.metadata, params.capture_id);
+                if let Some(save_to) = &params.save_to {
+                    let path = output::save_capture(&output, save_to, &capture, params.capture_id)?;
+                    metadata.push_str(&format!("\nSaved to: {}", path.display()));
+                }
+                let data = if scale < 1.0 {
+                    let scaled = capture::scale_image(&capture.image, scale);
+                    let transform = capture
+                        .transform
+                        .scaled(scaled.width() as f64 / capture.image.width().max(1) as f64);
+                    metadata.push_str(&format!(
+                        "\nScaled to {}x{}, transform: {}",
+                        scaled.width(),
+                        scaled.height(),
+                        serde_json::to_string(&transform).unwrap_or_default()
+                    ));
+                    capture::encode_image(&scaled, encoding)?
+                } else {
+                    capture::encode_image(&capture.image, encoding)?
+                };
+                let image =
+                    base64::Engine::encode(&base64::engine::general_purpose::STANDARD, data);
+                Ok((metadata, image))
+            })
+            .await
+            .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+            .map_err(|e| McpError::internal_error(e, None))?;
+
+        Ok(CallToolResult::success(vec![
+            Content::text(metadata),
+            Content::image(image, encoding.mime_type().to_string()),
+        ]))
+    }
+
+    #[tool(
+        description = "Block until a monitor, window or region changes (mode \"change\") or stops changing for stable_ms (mode \"stable\"), then return the final screenshot. Replaces sleep-and-retry loops"
+    )]
+    async fn wait_for_change(
+        &self,
+        params: Parameters<WaitForChangeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        let source = params
+            .target
+            .source()
+            .map_err(|e| McpError::invalid_params(e, None))?;
+        let options = WaitOptions {
+            mode: params.mode.unwrap_or_default(),
+            threshold_percent: params.threshold_percent.unwrap_or(0.0),
+            pixel_threshold: params.pixel_threshold.unwrap_or(DEFAULT_PIXEL_THRESHOLD),
+            poll_interval: Duration::from_millis(params.poll_interval_ms.unwrap_or(250).max(10)),
+            timeout: Duration::from_millis(params.timeout_ms.unwrap_or(10_000)),
+            stable_for: Duration::from_millis(params.stable_ms.unwrap_or(1000)),
+        };
+        let captures = self.captures.clone();
+        let output = self.config.output.clone();
+        let capture_options = self.capture_options(params.save_to, params.delivery);
 
-            let base64_image =
-                base64::Engine::encode(&base64::engine::general_purpose::STANDARD, buffer);
+        let (report, metadata, image) =
+            tokio::task::spawn_blocking(move || -> Result<(String, String, Content), String> {
+                let (report, capture) = change_wait::wait_for_change(source, options)?;
+                let report = serde_json::to_string_pretty(&report)
+                    .map_err(|e| format!("JSON serialization failed: {}", e))?;
+                let (metadata, image) =
+                    store_capture(&captures, &output, capture, &capture_options)?;
+                Ok((report, metadata, image))
+            })
+            .await
+            .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+            .map_err(|e| McpError::internal_error(e, None))?;
 
-            let metadata = format!(
-                "Window: {} [{}] (ID: {}, Size: {}x{})",
-                window.title().unwrap_or_default(),
-                window.app_name().unwrap_or_default(),
-                window.id().unwrap_or(0),
-                window.width().unwrap_or(0),
-                window.height().unwrap_or(0)
-            );
+        Ok(CallToolResult::success(vec![
+            Content::text(report),
+            Content::text(metadata),
+            image,
+        ]))
+    }
+
+    #[tool(
+        description = "Block until a window matching title and/or app_name appears, disappears or is focused, and return its record. Sends progress notifications while waiting and stops when the request is cancelled"
+    )]
+    async fn wait_for_window(
+        &self,
+        params: Parameters<WaitForWindowParams>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        if params.title.is_none() && params.app_name.is_none() {
+            return Err(McpError::invalid_params(
+                "Give a title and/or app_name to match",
+                None,
+            ));
+        }
+        let matcher = WindowMatcher {
+            title: params.title,
+            app_name: params.app_name,
+        };
+        let state = params.state.unwrap_or_default();
+        let timeout = Duration::from_millis(params.timeout_ms.unwrap_or(10_000));
+        let start = Instant::now();
+        let mut last_seen = None;
+        let mut cancelled = false;
+        let (satisfied, window) = loop {
+            let check_matcher = matcher.clone();
+            let (satisfied, window) = tokio::task::spawn_blocking(move || {
+                window_ops::check_window_state(&check_matcher, state)
+            })
+            .await
+            .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+            .map_err(|e| McpError::internal_error(e, None))?;
+
+            // For "disappeared", report the window that was last seen open
+            if state == WindowState::Disappeared {
+                if satisfied {
+                    break (true, last_seen);
+                }
+                last_seen = window;
+            } else if satisfied {
+                break (true, window);
+            }
+
+            let elapsed = start.elapsed();
+            if elapsed >= timeout {
+                break (false, last_seen);
+            }
 
-            Ok((metadata, base64_image))
+            report_progress(
+                &context,
+                elapsed.as_millis() as f64,
+                timeout.as_millis() as f64,
+                format!("Waiting for a window {} to be {}", matcher, state),
+            )
+            .await;
+
+            tokio::select! {
+                _ = tokio::time::sleep(window_ops::WINDOW_POLL_INTERVAL) => {}
+                _ = context.ct.cancelled() => {
+                    cancelled = true;
+                    break (false, last_seen);
+                }
+            }
+        };
+
+        let result = serde_json::to_string_pretty(&serde_json::json!({
+            "state": state,
+            "satisfied": satisfied,
+            "cancelled": cancelled,
+            "elapsed_ms": start.elapsed().as_millis() as u64,
+            "window": window,
+        }))
+        .map_err(|e| McpError::internal_error(format!("JSON serialization failed: {}", e), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "Record a monitor, window or region as an animated GIF or APNG: captures frames at fps for duration_ms (or a fixed number of frames), merges identical consecutive frames and downscales to stay under max_bytes. The recording is kept in capture history like a screenshot. Sends progress notifications"
+    )]
+    async fn record_screen(
+        &self,
+        params: Parameters<RecordScreenParams>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        let source = params
+            .target
+            .source()
+            .map_err(|e| McpError::invalid_params(e, None))?;
+        let format = params.format.unwrap_or_default();
+        let fps = params.fps.unwrap_or(5).clamp(1, MAX_RECORDING_FPS);
+        let duration_ms = params.duration_ms.unwrap_or(3000);
+        if duration_ms > MAX_RECORDING_DURATION_MS {
+            return Err(McpError::invalid_params(
+                format!("duration_ms must be at most {}", MAX_RECORDING_DURATION_MS),
+                None,
+            ));
+        }
+        let frame_count = params
+            .frames
+            .unwrap_or_else(|| {
+                duration_ms
+                    .saturating_mul(fps as u64)
+                    .div_ceil(1000)
+                    .min(MAX_RECORDING_FRAMES as u64) as u32
+            })
+            .clamp(1, MAX_RECORDING_FRAMES);
+        let max_bytes = params.max_bytes.unwrap_or(DEFAULT_RECORDING_MAX_BYTES);
+        let include_cursor = params.include_cursor.unwrap_or(false);
+        let frame_interval = Duration::from_millis(1000 / fps as u64);
+        // One extra step for encoding
+        let total_steps = frame_count as f64 + 1.0;
+
+        let start = Instant::now();
+        let mut recording = Recording::new(frame_count);
+        let mut last_frame = None;
+        let mut ticker = tokio::time::interval(frame_interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        for index in 0..frame_count {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = context.ct.cancelled() => {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "Recording cancelled after {} of {} frames",
+                        index, frame_count
+                    ))]));
+                }
+            }
+
+            let mut capture =
+                tokio::task::spawn_blocking(move || with_cursor(source.capture()?, include_cursor))
+                    .await
+                    .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+                    .map_err(|e| McpError::internal_error(e, None))?;
+            // Only the last frame is kept at full size, as the still image of the recording
+            let image = if index + 1 == frame_count {
+                capture.image.clone()
+            } else {
+                std::mem::take(&mut capture.image)
+            };
+            recording
+                .push(image, frame_interval.as_millis() as u32)
+                .map_err(|e| McpError::internal_error(e, None))?;
+            last_frame = Some(capture);
+
+            report_progress(
+                &context,
+                (index + 1) as f64,
+                total_steps,
+                format!("Captured frame {}/{}", index + 1, frame_count),
+            )
+            .await;
+        }
+        let duration_ms = start.elapsed().as_millis() as u64;
+        let duplicates = recording.duplicates;
+
+        report_progress(
+            &context,
+            frame_count as f64,
+            total_steps,
+            format!("Encoding {} frames", recording.frames.len()),
+        )
+        .await;
+        let mut animation = tokio::task::spawn_blocking(move || {
+            recording::encode_animation(&recording, format, max_bytes)
         })
         .await
         .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
         .map_err(|e| McpError::internal_error(e, None))?;
 
+        let data = std::mem::take(&mut animation.data);
+        let last_frame =
+            last_frame.ok_or_else(|| McpError::internal_error("No frames were captured", None))?;
+        let capture = last_frame.with_animation(
+            format,
+            data,
+            &format!(
+                "Recording of {} frames over {} ms; the still image is the last frame",
+                frame_count, duration_ms
+            ),
+        );
+        let captures = self.captures.clone();
+        let output = self.config.output.clone();
+        let options = self.capture_options(params.save_to, params.delivery);
+        let (metadata, image) = tokio::task::spawn_blocking(move || {
+            store_capture(&captures, &output, capture, &options)
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+        .map_err(|e| McpError::internal_error(e, None))?;
+
+        let summary = serde_json::to_string_pretty(&serde_json::json!({
+            "captured_frames": frame_count,
+            "duplicate_frames": duplicates,
+            "duration_ms": duration_ms,
+            "animation": animation,
+        }))
+        .map_err(|e| McpError::internal_error(format!("JSON serialization failed: {}", e), None))?;
+
         Ok(CallToolResult::success(vec![
-            Content::text(result.0),
-            Content::image(result.1, "image/png".to_string()),
+            Content::text(summary),
+            Content::text(metadata),
+            image,
         ]))
     }
 
-    #[tool(description = "Close a window")]
+    #[tool(
+        description = "Close a window. Pass wait_timeout_ms to wait and report whether it closed, stayed open, or opened a new dialog",
+        annotations(destructive_hint = true, read_only_hint = false)
+    )]
     async fn close_window(
         &self,
         params: Parameters<CloseWindowParams>,
+        peer: Peer<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
         let window_id = params.0.window_id;
 
+        if self.config.confirm.confirms_close() {
+            let label = tokio::task::spawn_blocking(move || {
+                window_ops::find_window(window_id).map(|w| {
+                    format!(
+                        "'{}' [{}]",
+                        w.title().unwrap_or_default(),
+                        w.app_name().unwrap_or_default()
+                    )
+                })
+            })
+            .await
+            .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+            .map_err(|e| McpError::internal_error(e, None))?;
+
+            if !self
+                .confirm_action(&peer, format!("Close {}?", label))
+                .await?
+            {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Close of window {} (ID: {}) was not confirmed",
+                    label, window_id
+                ))]));
+            }
+        }
+
+        let wait_timeout = params.0.wait_timeout_ms.map(Duration::from_millis);
+
         // Perform the close window operation in a blocking thread
-        let result =
-            tokio::task::spawn_blocking(move || window_ops::close_window_with_info(window_id))
-                .await
-                .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
-                .map_err(|e| McpError::internal_error(e, None))?;
+        let result = tokio::task::spawn_blocking(move || -> Result<String, String> {
+            match wait_timeout {
+                Some(timeout) => {
+                    let report = window_ops::close_window_and_wait(window_id, timeout)?;
+                    serde_json::to_string_pretty(&report)
+                        .map_err(|e| format!("JSON serialization failed: {}", e))
+                }
+                None => window_ops::close_window_with_info(window_id),
+            }
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+        .map_err(|e| McpError::internal_error(e, None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(description = "Activate a window and raise it above other windows")]
+    async fn focus_window(
+        &self,
+        params: Parameters<WindowParams>,
+    ) -> Result<CallToolResult, McpError> {
+        Self::run_window_action(params.0.window_id, WindowAction::Focus).await
+    }
+
+    #[tool(description = "Move and/or resize a window. Omitted fields keep their current value")]
+    async fn move_resize_window(
+        &self,
+        params: Parameters<MoveResizeWindowParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        let action = WindowAction::MoveResize {
+            x: params.x,
+            y: params.y,
+            width: params.width,
+            height: params.height,
+        };
+        Self::run_window_action(params.window_id, action).await
+    }
+
+    #[tool(description = "Minimize a window")]
+    async fn minimize_window(
+        &self,
+        params: Parameters<WindowParams>,
+    ) -> Result<CallToolResult, McpError> {
+        Self::run_window_action(params.0.window_id, WindowAction::Minimize).await
+    }
+
+    #[tool(description = "Maximize a window")]
+    async fn maximize_window(
+        &self,
+        params: Parameters<WindowParams>,
+    ) -> Result<CallToolResult, McpError> {
+        Self::run_window_action(params.0.window_id, WindowAction::Maximize).await
+    }
+
+    #[tool(description = "Restore a minimized, maximized or fullscreen window")]
+    async fn restore_window(
+        &self,
+        params: Parameters<WindowParams>,
+    ) -> Result<CallToolResult, McpError> {
+        Self::run_window_action(params.0.window_id, WindowAction::Restore).await
+    }
+
+    #[tool(description = "Enter or leave fullscreen for a window")]
+    async fn set_window_fullscreen(
+        &self,
+        params: Parameters<FullscreenWindowParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let action = WindowAction::Fullscreen(params.0.fullscreen.unwrap_or(true));
+        Self::run_window_action(params.0.window_id, action).await
+    }
+
+    #[tool(description = "Move a window to another workspace (virtual desktop)")]
+    async fn move_window_to_workspace(
+        &self,
+        params: Parameters<MoveToWorkspaceParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let action = WindowAction::MoveToWorkspace(params.0.workspace);
+        Self::run_window_action(params.0.window_id, action).await
+    }
+
+    #[tool(
+        description = "List workspaces (virtual desktops) with their names and the current workspace"
+    )]
+    async fn get_workspaces(
+        &self,
+        _params: Parameters<EmptyParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let result = tokio::task::spawn_blocking(move || -> Result<String, String> {
+            let workspaces = window_ops::list_workspaces()?;
+            serde_json::to_string_pretty(&workspaces)
+                .map_err(|e| format!("JSON serialization failed: {}", e))
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+        .map_err(|e| McpError::internal_error(e, None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(description = "Switch the workspace (virtual desktop) being shown")]
+    async fn switch_workspace(
+        &self,
+        params: Parameters<WorkspaceParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let index = params.0.workspace;
+
+        let result = tokio::task::spawn_blocking(move || -> Result<String, String> {
+            window_ops::switch_workspace(index)?;
+            Ok(format!("Switched to workspace {}", index))
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+        .map_err(|e| McpError::internal_error(e, None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "Force-terminate the process that owns a window (SIGTERM, then SIGKILL after a grace period). Use only when close_window has no effect. Disabled unless the server runs with --allow-kill",
+        annotations(destructive_hint = true, read_only_hint = false)
+    )]
+    async fn kill_window_process(
+        &self,
+        params: Parameters<KillWindowProcessParams>,
+        peer: Peer<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        if !self.config.allow_kill {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "kill_window_process is disabled; start the server with --allow-kill to enable it",
+            )]));
+        }
+
+        let window_id = params.0.window_id;
+        let grace_period_ms = params.0.grace_period_ms.unwrap_or(3000);
+        if grace_period_ms > MAX_KILL_GRACE_PERIOD_MS {
+            return Err(McpError::invalid_params(
+                format!(
+                    "grace_period_ms must be at most {}",
+                    MAX_KILL_GRACE_PERIOD_MS
+                ),
+                None,
+            ));
+        }
+        let grace_period = Duration::from_millis(grace_period_ms);
+
+        // Resolve the owning process before asking for confirmation
+        let (pid, start_time, label) =
+            tokio::task::spawn_blocking(move || -> Result<(u32, Option<u64>, String), String> {
+                let window = window_ops::find_window(window_id)?;
+                let pid = window_ops::window_pid(window_id)?;
+                let label = format!(
+                    "'{}' [{}]",
+                    window.title().unwrap_or_default(),
+                    window.app_name().unwrap_or_default()
+                );
+                Ok((pid, process_ops::process_start_time(pid), label))
+            })
+            .await
+            .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+            .map_err(|e| McpError::internal_error(e, None))?;
+
+        if self.config.confirm.confirms_destructive()
+            && !self
+                .confirm_action(
+                    &peer,
+                    format!("Kill process {} owning window {}?", pid, label),
+                )
+                .await?
+        {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Killing process {} owning window {} was not confirmed",
+                pid, label
+            ))]));
+        }
+
+        let result = tokio::task::spawn_blocking(move || -> Result<String, String> {
+            // The window may have closed and its PID been reused while the user was asked
+            let current = window_ops::window_pid(window_id)?;
+            if current != pid || process_ops::process_start_time(pid) != start_time {
+                return Err(format!(
+                    "Window ID {} no longer belongs to process {}; nothing was killed",
+                    window_id, pid
+                ));
+            }
+            let report = process_ops::terminate_process(pid, grace_period)?;
+            serde_json::to_string_pretty(&report)
+                .map_err(|e| format!("JSON serialization failed: {}", e))
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+        .map_err(|e| McpError::internal_error(e, None))?;
 
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
 }
 
+impl WindowCapServer {
+    /// Combine per-call capture parameters with the server defaults
+    pub(crate) fn capture_options(
+        &self,
+        save_to: Option<String>,
+        delivery: Option<ImageDelivery>,
+    ) -> CaptureOptions {
+        CaptureOptions::new(save_to, delivery.unwrap_or(self.config.image_delivery))
+    }
+
+    /// Take a capture in a blocking thread, store it and return it with its ID
+    async fn run_capture<F>(
+        &self,
+        options: CaptureOptions,
+        take: F,
+    ) -> Result<CallToolResult, McpError>
+    where
+        F: FnOnce() -> Result<Capture, String> + Send + 'static,
+    {
+        let captures = self.captures.clone();
+        let output = self.config.output.clone();
+
+        // 在阻塞线程中执行耗时的截图和编码操作
+        let result = tokio::task::spawn_blocking(move || -> Result<(String, Content), String> {
+            store_capture(&captures, &output, take()?, &options)
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+        .map_err(|e| McpError::internal_error(e, None))?;
+
+        Ok(CallToolResult::success(vec![
+            Content::text(result.0),
+            result.1,
+        ]))
+    }
+
+    /// Run a window management action in a blocking thread
+    async fn run_window_action(
+        window_id: u32,
+        action: WindowAction,
+    ) -> Result<CallToolResult, McpError> {
+        let result = tokio::task::spawn_blocking(move || {
+            window_ops::manage_window_with_info(window_id, action)
+        })
+        .await
+        .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+        .map_err(|e| McpError::internal_error(e, None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    /// Ask the user to confirm a destructive action via MCP elicitation
+    ///
+    /// Returns `Ok(false)` if the user declined or cancelled. When the client does not
+    /// support elicitation, the configured `ElicitationFallback` decides the outcome.
+    async fn confirm_action(
+        &self,
+        peer: &Peer<RoleServer>,
+        message: String,
+    ) -> Result<bool, McpError> {
+        match peer.elicit::<Confirmation>(message).await {
+            Ok(Some(confirmation)) => Ok(confirmation.confirm),
+            Ok(None)
+            | Err(ElicitationError::UserDeclined)
+            | Err(ElicitationError::UserCancelled)
+            | Err(ElicitationError::NoContent) => Ok(false),
+            Err(ElicitationError::CapabilityNotSupported) => {
+                Ok(self.config.elicitation_fallback == ElicitationFallback::Allow)
+            }
+            Err(e) => Err(McpError::internal_error(
+                format!("Confirmation request failed: {}", e),
+                None,
+            )),
+        }
+    }
+}
+
+/// Send a progress notification if the request carries a progress token
+async fn report_progress(
+    context: &RequestContext<RoleServer>,
+    progress: f64,
+    total: f64,
+    message: String,
+) {
+    if let Some(progress_token) = context.meta.get_progress_token() {
+        // Progress is best effort; a client that went away is noticed via cancellation
+        let _ = context
+            .peer
+            .notify_progress(ProgressNotificationParam {
+                progress_token,
+                progress,
+                total: Some(total),
+                message: Some(message),
+            })
+            .await;
+    }
+}
+
+/// What happens to a capture besides being stored
+pub(crate) struct CaptureOptions {
+    /// Also write the capture to this path
+    pub save_to: Option<String>,
+    pub delivery: ImageDelivery,
+    /// Overlays drawn on a stored copy that is returned instead; the original capture and
+    /// saved file stay clean
+    pub annotations: Vec<Annotation>,
+    pub grid_spacing: u32,
+}
+
+impl CaptureOptions {
+    pub(crate) fn new(save_to: Option<String>, delivery: ImageDelivery) -> Self {
+        Self {
+            save_to,
+            delivery,
+            annotations: Vec::new(),
+            grid_spacing: DEFAULT_GRID_SPACING,
+        }
+    }
+
+    /// Draw `annotations` on the returned image
+    pub(crate) fn annotated(
+        mut self,
+        annotations: Option<Vec<Annotation>>,
+        grid_spacing: Option<u32>,
+    ) -> Self {
+        self.annotations = annotations.unwrap_or_default();
+        self.grid_spacing = grid_spacing.unwrap_or(DEFAULT_GRID_SPACING);
+        self
+    }
+}
+
+/// Store a capture, returning its metadata with the capture ID and the image content
+///
+/// The capture is also written to `save_to` if it is given, and the path it was written
+/// to is added to the metadata. Depending on the delivery mode the image content is the
+/// PNG itself or a `capture://{id}` resource link. With annotations, the annotated image
+/// is stored as a second capture and delivered instead, and the grid spacing and outlined
+/// windows are added to the metadata. When captures are served over HTTP, the URL of the
+/// delivered capture is added as well.
+pub(crate) fn store_capture(
+    captures: &SessionCaptures,
+    output: &OutputConfig,
+    capture: Capture,
+    options: &CaptureOptions,
+) -> Result<(String, Content), String> {
+    let capture = Arc::new(capture);
+    let id = captures.insert(capture.clone());
+    let mut metadata = format!("{}\nCapture ID: {}", capture.metadata, id);
+    if let Some(save_to) = &options.save_to {
+        let path = output::save_capture(output, save_to, &capture, id)?;
+        metadata.push_str(&format!("\nSaved to: {}", path.display()));
+    }
+
+    // The annotated image is stored as a capture of its own, so that it can be delivered
+    // like any other while the original stays clean
+    let (id, capture) = if options.annotations.is_empty() {
+        (id, capture)
+    } else {
+        let annotated = annotate::annotate(&capture, &options.annotations, options.grid_spacing)?;
+        if let Some(spacing) = annotated.grid_spacing {
+            metadata.push_str(&format!("\nGrid spacing: {}", spacing));
+        }
+        if options.annotations.contains(&Annotation::Windows) {
+            let windows = serde_json::to_string(&annotated.windows)
+                .map_err(|e| format!("JSON serialization failed: {}", e))?;
+            metadata.push_str(&format!("\nOutlined windows: {}", windows));
+        }
+        let derived = Arc::new(capture.derived(
+            annotated.image,
+            &format!("Annotated copy of capture {}", id),
+        ));
+        let derived_id = captures.insert(derived.clone());
+        metadata.push_str(&format!("\nAnnotated capture ID: {}", derived_id));
+        (derived_id, derived)
+    };
+
+    if let Some(url) = captures.http_url(id) {
+        metadata.push_str(&format!("\nHTTP URL: {}", url));
+    }
+    Ok((metadata, deliver_capture(id, &capture, options.delivery)?))
+}
+
+/// Content item for a stored capture: the image itself or a `capture://{id}` resource link
+fn deliver_capture(id: u64, capture: &Capture, delivery: ImageDelivery) -> Result<Content, String> {
+    Ok(match delivery {
+        ImageDelivery::Inline => Content::image(capture.to_base64()?, capture.mime_type()),
+        ImageDelivery::ResourceLink => {
+            Content::resource_link(capture_resource(id, &capture.metadata, capture.mime_type()))
+        }
+    })
+}
+
+/// Draw the cursor onto a fresh capture if requested
+fn with_cursor(mut capture: Capture, include_cursor: bool) -> Result<Capture, String> {
+    if include_cursor {
+        cursor::composite_cursor(&mut capture)?;
+    }
+    Ok(capture)
+}
+
+/// Tool result for input tools when the server was started without `--allow-input`
+pub(crate) fn input_disabled() -> CallToolResult {
+    CallToolResult::error(vec![Content::text(
+        "Input tools are disabled; start the server with --allow-input to enable them",
+    )])
+}
+
+/// Describe a stored capture as an MCP resource
+fn capture_resource(id: u64, metadata: &str, mime_type: &str) -> RawResource {
+    let extension = mime_type.rsplit('/').next().unwrap_or("png");
+    RawResource {
+        uri: capture_store::capture_uri(id),
+        name: format!("capture-{}.{}", id, extension),
+        title: None,
+        description: metadata.lines().next().map(str::to_string),
+        mime_type: Some(mime_type.to_string()),
+        size: None,
+        icons: None,
+    }
+}
+
 #[tool_handler]
 impl ServerHandler for WindowCapServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .build(),
             server_info: Implementation {
                 name: "window-cap-mcp".to_string(),
                 version: "0.2.0".to_string(),
@@ -256,4 +1225,73 @@ impl ServerHandler for WindowCapServer {
             ),
         }
     }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        let resources = self
+            .captures
+            .list()
+            .into_iter()
+            .map(|summary| {
+                capture_resource(summary.id, &summary.metadata, summary.mime_type).no_annotation()
+            })
+            .collect();
+        Ok(ListResourcesResult {
+            resources,
+            next_cursor: None,
+        })
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, McpError> {
+        let template = RawResourceTemplate {
+            uri_template: format!("{}{{id}}", capture_store::CAPTURE_URI_SCHEME),
+            name: "capture".to_string(),
+            title: None,
+            description: Some(
+                "A stored capture by capture ID, as PNG or as the animation of a recording"
+                    .to_string(),
+            ),
+            mime_type: Some("image/png".to_string()),
+        };
+        Ok(ListResourceTemplatesResult {
+            resource_templates: vec![template.no_annotation()],
+            next_cursor: None,
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        let id = capture_store::parse_capture_uri(&request.uri).ok_or_else(|| {
+            McpError::resource_not_found(format!("Unknown resource {}", request.uri), None)
+        })?;
+        let capture = self
+            .captures
+            .require(id)
+            .map_err(|e| McpError::resource_not_found(e, None))?;
+
+        let mime_type = capture.mime_type();
+        let blob = tokio::task::spawn_blocking(move || capture.to_base64())
+            .await
+            .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+            .map_err(|e| McpError::internal_error(e, None))?;
+
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::BlobResourceContents {
+                uri: request.uri,
+                mime_type: Some(mime_type.to_string()),
+                blob,
+                meta: None,
+            }],
+        })
+    }
 }
@@ -0,0 +1,170 @@
+use crate::handler::{input_disabled, WindowCapServer};
+use crate::models::*;
+use crate::utils::input_ops;
+use crate::utils::window_ops::{self, WindowAction};
+use rmcp::{
+    handler::server::wrapper::Parameters, model::*, tool, tool_router, ErrorData as McpError,
+};
+use std::time::Duration;
+
+#[tool_router(router = input_tool_router, vis = "pub(crate)")]
+impl WindowCapServer {
+    #[tool(
+        description = "Move the mouse pointer to a position in desktop coordinates, or relative to a window if window_id is given",
+        annotations(read_only_hint = false)
+    )]
+    async fn mouse_move(
+        &self,
+        params: Parameters<MouseMoveParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        self.run_input(move || {
+            let (x, y) = input_ops::resolve_point(params.x, params.y, params.window_id)?;
+            input_ops::mouse_move(x, y)?;
+            Ok(format!("Moved pointer to ({}, {})", x, y))
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Click a mouse button at a position (desktop coordinates, or relative to window_id) or at the current pointer position",
+        annotations(read_only_hint = false)
+    )]
+    async fn mouse_click(
+        &self,
+        params: Parameters<MouseClickParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        let button = params.button.unwrap_or_default();
+        let count = params.count.unwrap_or(1);
+        self.run_input(move || {
+            let position = match (params.x, params.y) {
+                (Some(x), Some(y)) => Some(input_ops::resolve_point(x, y, params.window_id)?),
+                (None, None) => None,
+                _ => return Err("x and y must be given together".to_string()),
+            };
+            input_ops::mouse_click(position, button.number(), count)?;
+            Ok(match position {
+                Some((x, y)) => format!("Clicked {} time(s) at ({}, {})", count, x, y),
+                None => format!("Clicked {} time(s) at the current pointer position", count),
+            })
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Drag with a mouse button held from one position to another (desktop coordinates, or relative to window_id)",
+        annotations(read_only_hint = false)
+    )]
+    async fn mouse_drag(
+        &self,
+        params: Parameters<MouseDragParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        let button = params.button.unwrap_or_default();
+        let duration = Duration::from_millis(params.duration_ms.unwrap_or(300));
+        self.run_input(move || {
+            let from = input_ops::resolve_point(params.from_x, params.from_y, params.window_id)?;
+            let to = input_ops::resolve_point(params.to_x, params.to_y, params.window_id)?;
+            input_ops::mouse_drag(from, to, button.number(), duration)?;
+            Ok(format!(
+                "Dragged from ({}, {}) to ({}, {})",
+                from.0, from.1, to.0, to.1
+            ))
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Scroll the mouse wheel by a number of notches at a position or at the current pointer position",
+        annotations(read_only_hint = false)
+    )]
+    async fn mouse_scroll(
+        &self,
+        params: Parameters<MouseScrollParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        let delta_x = params.delta_x.unwrap_or(0);
+        let delta_y = params.delta_y.unwrap_or(0);
+        self.run_input(move || {
+            let position = match (params.x, params.y) {
+                (Some(x), Some(y)) => Some(input_ops::resolve_point(x, y, params.window_id)?),
+                (None, None) => None,
+                _ => return Err("x and y must be given together".to_string()),
+            };
+            input_ops::mouse_scroll(position, delta_x, delta_y)?;
+            Ok(format!("Scrolled by ({}, {}) notches", delta_x, delta_y))
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Type text as keyboard input, optionally focusing a window first. Supports any Unicode characters",
+        annotations(read_only_hint = false)
+    )]
+    async fn type_text(
+        &self,
+        params: Parameters<TypeTextParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        let delay = Duration::from_millis(params.delay_ms.unwrap_or(10));
+        self.run_input(move || {
+            focus_target(params.window_id)?;
+            input_ops::type_text(&params.text, delay)?;
+            Ok(format!(
+                "Typed {} character(s)",
+                params.text.chars().count()
+            ))
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Press a key chord such as \"ctrl+shift+t\", \"alt+f4\" or \"enter\", optionally focusing a window first",
+        annotations(read_only_hint = false)
+    )]
+    async fn press_keys(
+        &self,
+        params: Parameters<PressKeysParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        self.run_input(move || {
+            focus_target(params.window_id)?;
+            input_ops::press_keys(&params.keys)?;
+            Ok(format!("Pressed {}", params.keys))
+        })
+        .await
+    }
+}
+
+/// Time given to the window manager to apply a focus request before sending keys
+const FOCUS_SETTLE_DELAY: Duration = Duration::from_millis(100);
+
+/// Focus the window that keyboard input is meant for
+pub(crate) fn focus_target(window_id: Option<u32>) -> Result<(), String> {
+    if let Some(window_id) = window_id {
+        window_ops::find_window(window_id)?;
+        window_ops::manage_window(window_id, WindowAction::Focus)?;
+        std::thread::sleep(FOCUS_SETTLE_DELAY);
+    }
+    Ok(())
+}
+
+impl WindowCapServer {
+    /// Run an input operation in a blocking thread if input synthesis is allowed
+    pub(crate) async fn run_input<F>(&self, operation: F) -> Result<CallToolResult, McpError>
+    where
+        F: FnOnce() -> Result<String, String> + Send + 'static,
+    {
+        if !self.config.allow_input {
+            return Ok(input_disabled());
+        }
+
+        let result = tokio::task::spawn_blocking(operation)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Task join error: {}", e), None))?
+            .map_err(|e| McpError::internal_error(e, None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+}
@@ -1,5 +1,10 @@
+mod action_tools;
+pub mod capture_http;
 pub mod cli;
+pub mod config;
 pub mod handler;
+#[cfg(feature = "input")]
+mod input_tools;
 pub mod models;
 pub mod server;
 pub mod utils;
@@ -9,6 +14,7 @@ pub mod utils;
 pub mod python_bindings;
 
 // Re-export for Rust library users
+pub use config::ServerConfig;
 pub use handler::WindowCapServer;
 pub use models::*;
 
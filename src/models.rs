@@ -1,3 +1,10 @@
+use crate::config::ImageDelivery;
+use crate::utils::annotate::Annotation;
+use crate::utils::capture::{CaptureSource, ImageEncoding};
+use crate::utils::change_wait::WaitMode;
+use crate::utils::coordinates::{CoordinateSpace, SpaceRef};
+use crate::utils::recording::RecordingFormat;
+use crate::utils::window_ops::{WindowState, WindowType};
 use rmcp::schemars;
 use serde::{Deserialize, Serialize};
 
@@ -9,16 +16,558 @@ pub struct ScreenshotParams {
     /// Monitor index, uses primary monitor if not specified
     #[serde(skip_serializing_if = "Option::is_none")]
     pub monitor_index: Option<u32>,
+    /// Also write the image to this file or directory on the server; relative paths use the server's output directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub save_to: Option<String>,
+    /// "inline" embeds the PNG, "resource_link" returns a capture:// link to read later (default: server setting)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delivery: Option<ImageDelivery>,
+    /// Overlays to draw on the returned image: "grid" labels desktop coordinates, "windows" outlines each window with its ID and title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotate: Option<Vec<Annotation>>,
+    /// Grid spacing in desktop coordinates (default: 100, widened if lines would be too dense)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grid_spacing: Option<u32>,
+    /// Draw the mouse cursor onto the image at its current position (Linux only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_cursor: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, schemars::JsonSchema)]
 pub struct WindowScreenshotParams {
     /// Window ID
     pub window_id: u32,
+    /// Also write the image to this file or directory on the server; relative paths use the server's output directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub save_to: Option<String>,
+    /// "inline" embeds the PNG, "resource_link" returns a capture:// link to read later (default: server setting)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delivery: Option<ImageDelivery>,
+    /// Overlays to draw on the returned image: "grid" labels desktop coordinates, "windows" outlines each window with its ID and title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotate: Option<Vec<Annotation>>,
+    /// Grid spacing in desktop coordinates (default: 100, widened if lines would be too dense)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grid_spacing: Option<u32>,
+    /// Draw the mouse cursor onto the image at its current position (Linux only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_cursor: Option<bool>,
+}
+
+/// Per-channel difference up to which pixels count as unchanged
+pub const DEFAULT_PIXEL_THRESHOLD: u8 = 16;
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct DiffScreenshotsParams {
+    /// ID of the earlier capture to compare against, as returned with every screenshot
+    pub capture_id: u64,
+    /// Compare with this stored capture instead of capturing the same source again
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compare_to: Option<u64>,
+    /// Largest per-channel difference (0-255) still treated as unchanged (default: 16)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pixel_threshold: Option<u8>,
+    /// Also return an image with the changed regions outlined (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_image: Option<bool>,
+    /// "inline" embeds the highlight image, "resource_link" returns a capture:// link to read later (default: server setting)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delivery: Option<ImageDelivery>,
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct GetCaptureParams {
+    /// ID of a stored capture, as returned with every screenshot
+    pub capture_id: u64,
+    /// Image format: "png" or "jpeg" (default: "png")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<ImageEncoding>,
+    /// Resize factor between 0 and 1, e.g. 0.5 for half size (default: 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale: Option<f64>,
+    /// Also write the image to this file or directory on the server; relative paths use the server's output directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub save_to: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct RegionParams {
+    /// Left edge in desktop coordinates
+    pub x: i32,
+    /// Top edge in desktop coordinates
+    pub y: i32,
+    /// Width in desktop units
+    pub width: u32,
+    /// Height in desktop units
+    pub height: u32,
+}
+
+/// What to capture: one of a monitor, a window or a region
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct CaptureTargetParams {
+    /// Monitor index; the primary monitor is used if no target is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monitor_index: Option<u32>,
+    /// Window ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_id: Option<u32>,
+    /// Rectangle in desktop coordinates, on a single monitor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<RegionParams>,
+}
+
+impl CaptureTargetParams {
+    pub fn source(&self) -> Result<CaptureSource, String> {
+        match (self.monitor_index, self.window_id, &self.region) {
+            (index, None, None) => Ok(CaptureSource::Monitor {
+                index: index.map(|idx| idx as usize),
+            }),
+            (None, Some(window_id), None) => Ok(CaptureSource::Window { window_id }),
+            (None, None, Some(region)) => Ok(CaptureSource::Region {
+                x: region.x,
+                y: region.y,
+                width: region.width,
+                height: region.height,
+            }),
+            _ => Err("Give only one of monitor_index, window_id and region".to_string()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct WaitForChangeParams {
+    #[serde(flatten)]
+    pub target: CaptureTargetParams,
+    /// "change" returns as soon as the content changes, "stable" once it stops changing (default: "change")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<WaitMode>,
+    /// Share of changed pixels (0-100) that must be exceeded to count as a change (default: 0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold_percent: Option<f64>,
+    /// Largest per-channel difference (0-255) still treated as unchanged (default: 16)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pixel_threshold: Option<u8>,
+    /// Time between captures in milliseconds (default: 250)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_interval_ms: Option<u64>,
+    /// Give up after this many milliseconds and return the last capture (default: 10000)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// In "stable" mode, how long the content must stay unchanged in milliseconds (default: 1000)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stable_ms: Option<u64>,
+    /// Also write the image to this file or directory on the server; relative paths use the server's output directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub save_to: Option<String>,
+    /// "inline" embeds the PNG, "resource_link" returns a capture:// link to read later (default: server setting)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delivery: Option<ImageDelivery>,
+}
+
+/// Highest frame rate `record_screen` accepts
+pub const MAX_RECORDING_FPS: u32 = 30;
+
+/// Most frames `record_screen` captures in one call
+pub const MAX_RECORDING_FRAMES: u32 = 300;
+
+/// Longest `duration_ms` `record_screen` accepts
+pub const MAX_RECORDING_DURATION_MS: u64 = 60_000;
+
+/// Size cap for recordings when none is given
+pub const DEFAULT_RECORDING_MAX_BYTES: usize = 5 * 1024 * 1024;
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct RecordScreenParams {
+    #[serde(flatten)]
+    pub target: CaptureTargetParams,
+    /// "gif" or "apng" (default: "gif")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<RecordingFormat>,
+    /// Frames per second, 1-30 (default: 5)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fps: Option<u32>,
+    /// Recording length in milliseconds, at most 60000 (default: 3000); ignored if frames is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// Number of frames to capture, at most 300
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frames: Option<u32>,
+    /// Largest encoded size in bytes; frames are downscaled to fit (default: 5 MiB)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<usize>,
+    /// Also write the image to this file or directory on the server; relative paths use the server's output directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub save_to: Option<String>,
+    /// "inline" embeds the animation, "resource_link" returns a capture:// link to read later (default: server setting)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delivery: Option<ImageDelivery>,
+    /// Draw the mouse cursor into every frame at its current position (Linux only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_cursor: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct WaitForWindowParams {
+    /// Case-insensitive substring of the window title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Case-insensitive substring of the application name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_name: Option<String>,
+    /// "appeared", "disappeared" or "focused" (default: "appeared")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<WindowState>,
+    /// Give up after this many milliseconds (default: 10000)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, schemars::JsonSchema)]
 pub struct CloseWindowParams {
     /// Window ID
     pub window_id: u32,
+    /// Wait up to this many milliseconds and report whether the window actually closed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wait_timeout_ms: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct WindowListParams {
+    /// Window types to include (default: ["normal", "dialog"]); windows of unknown type count as normal
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_types: Option<Vec<WindowType>>,
+    /// Include the owning process (PID, executable, command line, parent PID, memory) of each window (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_process: Option<bool>,
+}
+
+/// Most windows shown in one contact sheet
+pub const MAX_THUMBNAILS: usize = 64;
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct WindowThumbnailsParams {
+    /// Window types to include (default: ["normal", "dialog"]); windows of unknown type count as normal
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_types: Option<Vec<WindowType>>,
+    /// Largest thumbnail width in pixels, 64-1024 (default: 320)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail_width: Option<u32>,
+    /// Largest thumbnail height in pixels, 64-1024 (default: 200)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail_height: Option<u32>,
+    /// Cells per row (default: a roughly square grid)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub columns: Option<u32>,
+    /// Also write the image to this file or directory on the server; relative paths use the server's output directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub save_to: Option<String>,
+    /// "inline" embeds the PNG, "resource_link" returns a capture:// link to read later (default: server setting)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delivery: Option<ImageDelivery>,
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct WindowPropertiesParams {
+    /// Window ID
+    pub window_id: u32,
+    /// Additional property names to read, e.g. ["_NET_WM_ICON_NAME", "_GTK_APPLICATION_ID"]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub atoms: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct WorkspaceParams {
+    /// Workspace index, starting at 0
+    pub workspace: u32,
 }
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MoveToWorkspaceParams {
+    /// Window ID
+    pub window_id: u32,
+    /// Workspace index, starting at 0
+    pub workspace: u32,
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct TransformCoordinatesParams {
+    /// X coordinate in the source space
+    pub x: f64,
+    /// Y coordinate in the source space
+    pub y: f64,
+    /// Source space
+    pub from: SpaceParams,
+    /// Target space
+    pub to: SpaceParams,
+}
+
+/// One side of a coordinate conversion
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SpaceParams {
+    /// "desktop", "monitor" (monitor screenshot pixel) or "window" (window screenshot pixel)
+    pub space: CoordinateSpace,
+    /// Monitor index for the monitor space; defaults to the primary monitor, or the monitor containing the point when converting to monitor pixels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monitor_index: Option<u32>,
+    /// Window ID, required for the window space
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_id: Option<u32>,
+    /// Factor this side's screenshot was resized by after capture, e.g. 0.5 for a half-size image (default: 1.0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capture_scale: Option<f64>,
+}
+
+impl SpaceParams {
+    pub fn space_ref(&self) -> Result<SpaceRef, String> {
+        let capture_scale = self.capture_scale.unwrap_or(1.0);
+        if !(capture_scale > 0.0 && capture_scale.is_finite()) {
+            return Err("capture_scale must be a positive number".to_string());
+        }
+        Ok(SpaceRef {
+            space: self.space,
+            monitor_index: self.monitor_index.map(|idx| idx as usize),
+            window_id: self.window_id,
+            capture_scale,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct PointParams {
+    /// X coordinate in desktop coordinates
+    pub x: i32,
+    /// Y coordinate in desktop coordinates
+    pub y: i32,
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct WindowParams {
+    /// Window ID
+    pub window_id: u32,
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MoveResizeWindowParams {
+    /// Window ID
+    pub window_id: u32,
+    /// New X position in desktop coordinates, unchanged if not specified
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<i32>,
+    /// New Y position in desktop coordinates, unchanged if not specified
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<i32>,
+    /// New width, unchanged if not specified
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    /// New height, unchanged if not specified
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct FullscreenWindowParams {
+    /// Window ID
+    pub window_id: u32,
+    /// Enter fullscreen when true (default), leave it when false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fullscreen: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MouseButton {
+    #[default]
+    Left,
+    Middle,
+    Right,
+}
+
+impl MouseButton {
+    /// X11 button number
+    pub fn number(self) -> u32 {
+        match self {
+            MouseButton::Left => 1,
+            MouseButton::Middle => 2,
+            MouseButton::Right => 3,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MouseMoveParams {
+    /// X coordinate, in desktop coordinates or relative to the window if window_id is given
+    pub x: i32,
+    /// Y coordinate, in desktop coordinates or relative to the window if window_id is given
+    pub y: i32,
+    /// Window the coordinates are relative to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_id: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MouseClickParams {
+    /// X coordinate to click at, uses the current pointer position if not specified
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<i32>,
+    /// Y coordinate to click at, uses the current pointer position if not specified
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<i32>,
+    /// Window the coordinates are relative to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_id: Option<u32>,
+    /// Mouse button (default left)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub button: Option<MouseButton>,
+    /// Number of clicks, e.g. 2 for a double click, 1-10 (default 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MouseDragParams {
+    /// Start X coordinate
+    pub from_x: i32,
+    /// Start Y coordinate
+    pub from_y: i32,
+    /// End X coordinate
+    pub to_x: i32,
+    /// End Y coordinate
+    pub to_y: i32,
+    /// Window the coordinates are relative to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_id: Option<u32>,
+    /// Mouse button to hold (default left)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub button: Option<MouseButton>,
+    /// Time spent moving from start to end in milliseconds, at most 10000 (default 300)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MouseScrollParams {
+    /// X coordinate to scroll at, uses the current pointer position if not specified
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<i32>,
+    /// Y coordinate to scroll at, uses the current pointer position if not specified
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<i32>,
+    /// Window the coordinates are relative to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_id: Option<u32>,
+    /// Horizontal scroll in wheel notches, -100 to 100, positive scrolls right
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delta_x: Option<i32>,
+    /// Vertical scroll in wheel notches, -100 to 100, positive scrolls down
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delta_y: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct TypeTextParams {
+    /// Text to type, may contain any Unicode characters
+    pub text: String,
+    /// Window to focus before typing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_id: Option<u32>,
+    /// Delay between characters in milliseconds (default 10)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay_ms: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct PressKeysParams {
+    /// Key chord joined with '+', e.g. "ctrl+shift+t", "alt+f4", "enter"
+    pub keys: String,
+    /// Window to focus before pressing the keys
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_id: Option<u32>,
+}
+
+/// A single step of a `run_actions` macro
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Clone)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ActionStep {
+    /// Activate and raise a window
+    FocusWindow { window_id: u32 },
+    /// Click at a position (desktop coordinates, or relative to window_id)
+    Click {
+        x: i32,
+        y: i32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        window_id: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        button: Option<MouseButton>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        count: Option<u32>,
+    },
+    /// Type text, optionally focusing a window first
+    Type {
+        text: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        window_id: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        delay_ms: Option<u64>,
+    },
+    /// Press a key chord such as "ctrl+s"
+    PressKeys {
+        keys: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        window_id: Option<u32>,
+    },
+    /// Pause for a number of milliseconds, at most 60000
+    Wait { ms: u64 },
+    /// Wait until a window whose title and/or app name contains the given text exists
+    WaitForWindow {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        app_name: Option<String>,
+        /// Timeout in milliseconds (default 5000, at most 60000)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        timeout_ms: Option<u64>,
+    },
+    /// Capture a screenshot of a window
+    CaptureWindow {
+        window_id: u32,
+        /// Also write the image to this file or directory on the server
+        #[serde(skip_serializing_if = "Option::is_none")]
+        save_to: Option<String>,
+    },
+    /// Capture a screenshot of a rectangle in desktop coordinates
+    CaptureRegion {
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        /// Also write the image to this file or directory on the server
+        #[serde(skip_serializing_if = "Option::is_none")]
+        save_to: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct RunActionsParams {
+    /// Steps to execute in order; execution stops at the first failing step
+    pub steps: Vec<ActionStep>,
+}
+
+/// Longest grace period `kill_window_process` waits between SIGTERM and SIGKILL
+pub const MAX_KILL_GRACE_PERIOD_MS: u64 = 30_000;
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct KillWindowProcessParams {
+    /// Window ID whose owning process should be terminated
+    pub window_id: u32,
+    /// Milliseconds to wait after SIGTERM before sending SIGKILL (default 3000, max 30000)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grace_period_ms: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
+pub struct Confirmation {
+    /// Set to true to proceed with the action
+    pub confirm: bool,
+}
+
+rmcp::elicit_safe!(Confirmation);
@@ -1,3 +1,4 @@
+use crate::utils::output::DEFAULT_FILENAME_TEMPLATE;
 use crate::utils::window_ops;
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
@@ -300,6 +301,18 @@ fn close_window(window_id: u32) -> PyResult<String> {
 ///     http (bool): Use Streamable HTTP protocol
 ///     port (int): Port to listen on (for HTTP/SSE mode)
 ///     host (str): Host to bind to (for HTTP/SSE mode)
+///     confirm (str): Which destructive tools require user confirmation
+///         ("never", "close" or "destructive")
+///     elicitation_fallback (str): What to do when confirmation is required but the
+///         client lacks elicitation support ("allow" or "deny")
+///     allow_kill (bool): Enable the kill_window_process tool
+///     allow_input (bool): Enable the mouse and keyboard input tools
+///     capture_history (int): Number of recent captures kept for later tools
+///     capture_history_mb (int): Memory limit for kept captures in MiB
+///     output_dir (str | None): Directory captures are saved to for relative save_to paths
+///     filename_template (str): File name template used when save_to names a directory
+///     write_roots (list[str]): Additional directories save_to may write into
+///     image_delivery (str): How capture tools return images ("inline" or "resource_link")
 ///
 /// Examples:
 ///     >>> import window_cap_mcp as wc
@@ -312,9 +325,46 @@ fn close_window(window_id: u32) -> PyResult<String> {
 ///     >>> # Run in HTTP mode
 ///     >>> wc.run_server(http=True, port=3000, host="0.0.0.0")
 #[pyfunction]
-#[pyo3(signature = (sse=false, http=false, port=8080, host="127.0.0.1".to_string()))]
-fn run_server(py: Python, sse: bool, http: bool, port: u16, host: String) -> PyResult<()> {
+#[pyo3(signature = (
+    sse=false,
+    http=false,
+    port=8080,
+    host="127.0.0.1".to_string(),
+    confirm="close".to_string(),
+    elicitation_fallback="allow".to_string(),
+    allow_kill=false,
+    allow_input=false,
+    capture_history=32,
+    capture_history_mb=256,
+    output_dir=None,
+    filename_template=DEFAULT_FILENAME_TEMPLATE.to_string(),
+    write_roots=Vec::new(),
+    image_delivery="inline".to_string(),
+))]
+#[allow(clippy::too_many_arguments)]
+fn run_server(
+    py: Python,
+    sse: bool,
+    http: bool,
+    port: u16,
+    host: String,
+    confirm: String,
+    elicitation_fallback: String,
+    allow_kill: bool,
+    allow_input: bool,
+    capture_history: usize,
+    capture_history_mb: usize,
+    output_dir: Option<String>,
+    filename_template: String,
+    write_roots: Vec<String>,
+    image_delivery: String,
+) -> PyResult<()> {
+    use crate::capture_http;
+    use crate::config::{ConfirmPolicy, ElicitationFallback, ImageDelivery, ServerConfig};
     use crate::handler::WindowCapServer;
+    use crate::utils::capture_store::{CaptureLimits, CaptureStore};
+    use crate::utils::output::OutputConfig;
+    use clap::ValueEnum;
     use rmcp::{
         transport::{
             sse_server::SseServer,
@@ -327,6 +377,42 @@ fn run_server(py: Python, sse: bool, http: bool, port: u16, host: String) -> PyR
         ServiceExt,
     };
     use std::net::SocketAddr;
+    use std::path::PathBuf;
+
+    let server_config = ServerConfig {
+        confirm: ConfirmPolicy::from_str(&confirm, true).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid confirm policy: {}",
+                e
+            ))
+        })?,
+        elicitation_fallback: ElicitationFallback::from_str(&elicitation_fallback, true).map_err(
+            |e| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Invalid elicitation fallback: {}",
+                    e
+                ))
+            },
+        )?,
+        allow_kill,
+        allow_input,
+        capture_limits: CaptureLimits {
+            max_captures: capture_history,
+            max_bytes: capture_history_mb * 1024 * 1024,
+        },
+        output: OutputConfig {
+            dir: output_dir.map(PathBuf::from),
+            filename_template,
+            write_roots: write_roots.into_iter().map(PathBuf::from).collect(),
+        },
+        image_delivery: ImageDelivery::from_str(&image_delivery, true).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid image delivery: {}",
+                e
+            ))
+        })?,
+    };
+    let captures = Arc::new(CaptureStore::new(server_config.capture_limits));
 
     // Release GIL for async operations
     py.detach(|| {
@@ -357,7 +443,10 @@ fn run_server(py: Python, sse: bool, http: bool, port: u16, host: String) -> PyR
                             e
                         ))
                     })?
-                    .with_service(WindowCapServer::new);
+                    .with_service(move || {
+                        WindowCapServer::with_config(server_config.clone())
+                            .with_capture_store(captures.clone())
+                    });
 
                 eprintln!("SSE server started, visit http://{}", addr);
                 tokio::signal::ctrl_c().await.map_err(|e| {
@@ -378,8 +467,16 @@ fn run_server(py: Python, sse: bool, http: bool, port: u16, host: String) -> PyR
                 })?;
                 eprintln!("Binding to: {}", addr);
 
+                // Capture results link to the capture routes served next to the MCP endpoint
+                captures.set_http_base_url(format!("http://{}", addr));
                 let session_manager = Arc::new(LocalSessionManager::default());
-                let service_factory = || Ok(WindowCapServer::new());
+                let service_factory = {
+                    let captures = captures.clone();
+                    move || {
+                        Ok(WindowCapServer::with_config(server_config.clone())
+                            .with_capture_store(captures.clone()))
+                    }
+                };
                 let config = StreamableHttpServerConfig::default();
                 let http_service =
                     StreamableHttpService::new(service_factory, session_manager, config);
@@ -400,6 +497,7 @@ fn run_server(py: Python, sse: bool, http: bool, port: u16, host: String) -> PyR
                     })?;
                     eprintln!("Accepted connection from: {}", peer_addr);
                     let http_service = http_service.clone();
+                    let captures = captures.clone();
                     tokio::spawn(async move {
                         use hyper_util::rt::TokioIo;
                         use hyper_util::server::conn::auto::Builder;
@@ -408,7 +506,15 @@ fn run_server(py: Python, sse: bool, http: bool, port: u16, host: String) -> PyR
                         let service = http_service.clone();
                         let hyper_service = hyper::service::service_fn(move |req| {
                             let mut svc = service.clone();
-                            async move { svc.call(req).await }
+                            let captures = captures.clone();
+                            async move {
+                                if let Some(response) =
+                                    capture_http::serve_capture(&captures, &req).await
+                                {
+                                    return Ok(response);
+                                }
+                                svc.call(req).await
+                            }
                         });
                         if let Err(e) = Builder::new(hyper_util::rt::TokioExecutor::new())
                             .serve_connection(io, hyper_service)
@@ -420,7 +526,7 @@ fn run_server(py: Python, sse: bool, http: bool, port: u16, host: String) -> PyR
                 }
             } else {
                 eprintln!("Starting server in STDIO mode...");
-                let server = WindowCapServer::new();
+                let server = WindowCapServer::with_config(server_config);
                 let service = server.serve(stdio()).await.map_err(|e| {
                     PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
                         "Failed to start STDIO server: {}",
@@ -451,7 +557,7 @@ fn run_server(py: Python, sse: bool, http: bool, port: u16, host: String) -> PyR
 ///     get_window_count() -> int: Get count of windows
 ///     capture_window(window_id: int) -> str: Capture screenshot from window (returns base64 PNG)
 ///     close_window(window_id: int) -> str: Close a window by its ID
-///     run_server(sse: bool = False, http: bool = False, port: int = 8080, host: str = "127.0.0.1") -> None: Run MCP server
+///     run_server(sse: bool = False, http: bool = False, port: int = 8080, host: str = "127.0.0.1", confirm: str = "close", elicitation_fallback: str = "allow", allow_kill: bool = False, allow_input: bool = False, capture_history: int = 32, capture_history_mb: int = 256, output_dir: str | None = None, filename_template: str = "{date}/{app}-{window_id}-{ts}.png", write_roots: list[str] = [], image_delivery: str = "inline") -> None: Run MCP server
 #[pymodule]
 fn window_cap_mcp(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(get_monitors, m)?)?;
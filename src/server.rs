@@ -1,5 +1,7 @@
+use crate::capture_http;
 use crate::cli::Cli;
 use crate::handler::WindowCapServer;
+use crate::utils::capture_store::CaptureStore;
 use clap::Parser;
 use rmcp::{
     transport::{
@@ -17,15 +19,17 @@ use std::sync::Arc;
 
 pub async fn run_server() -> anyhow::Result<()> {
     let cli = Cli::parse();
+    let server_config = cli.server_config();
+    let captures = Arc::new(CaptureStore::new(server_config.capture_limits));
 
     if cli.sse {
         eprintln!("Starting server in SSE mode...");
         let addr: SocketAddr = format!("{}:{}", cli.host, cli.port).parse()?;
         eprintln!("Binding to: {}", addr);
 
-        let ct = SseServer::serve(addr)
-            .await?
-            .with_service(WindowCapServer::new);
+        let ct = SseServer::serve(addr).await?.with_service(move || {
+            WindowCapServer::with_config(server_config.clone()).with_capture_store(captures.clone())
+        });
 
         eprintln!("SSE server started, visit http://{}", addr);
         tokio::signal::ctrl_c().await?;
@@ -36,8 +40,16 @@ pub async fn run_server() -> anyhow::Result<()> {
         let addr: SocketAddr = format!("{}:{}", cli.host, cli.port).parse()?;
         eprintln!("Binding to: {}", addr);
 
+        // Capture results link to the capture routes served next to the MCP endpoint
+        captures.set_http_base_url(format!("http://{}", addr));
         let session_manager = Arc::new(LocalSessionManager::default());
-        let service_factory = || Ok(WindowCapServer::new());
+        let service_factory = {
+            let captures = captures.clone();
+            move || {
+                Ok(WindowCapServer::with_config(server_config.clone())
+                    .with_capture_store(captures.clone()))
+            }
+        };
         let config = StreamableHttpServerConfig::default();
         let http_service = StreamableHttpService::new(service_factory, session_manager, config);
         let listener = tokio::net::TcpListener::bind(addr).await?;
@@ -47,6 +59,7 @@ pub async fn run_server() -> anyhow::Result<()> {
             let (stream, peer_addr) = listener.accept().await?;
             eprintln!("Accepted connection from: {}", peer_addr);
             let http_service = http_service.clone();
+            let captures = captures.clone();
             tokio::spawn(async move {
                 use hyper_util::rt::TokioIo;
                 use hyper_util::server::conn::auto::Builder;
@@ -55,7 +68,13 @@ pub async fn run_server() -> anyhow::Result<()> {
                 let service = http_service.clone();
                 let hyper_service = hyper::service::service_fn(move |req| {
                     let mut svc = service.clone();
-                    async move { svc.call(req).await }
+                    let captures = captures.clone();
+                    async move {
+                        if let Some(response) = capture_http::serve_capture(&captures, &req).await {
+                            return Ok(response);
+                        }
+                        svc.call(req).await
+                    }
                 });
                 if let Err(e) = Builder::new(hyper_util::rt::TokioExecutor::new())
                     .serve_connection(io, hyper_service)
@@ -67,7 +86,7 @@ pub async fn run_server() -> anyhow::Result<()> {
         }
     } else {
         eprintln!("Starting server in STDIO mode...");
-        let server = WindowCapServer::new();
+        let server = WindowCapServer::with_config(server_config).with_capture_store(captures);
         let service = server.serve(stdio()).await?;
         service.waiting().await?;
     }
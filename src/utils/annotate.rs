@@ -0,0 +1,260 @@
+use image::{Rgba, RgbaImage};
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+use super::capture::{Capture, CaptureSource};
+use super::coordinates::CaptureTransform;
+use super::drawing::{self, GLYPH_HEIGHT};
+use super::geometry::Rect;
+use super::window_ops::{self, WindowType};
+
+/// Grid spacing in desktop units when none is given
+pub const DEFAULT_GRID_SPACING: u32 = 100;
+
+/// Closest two grid lines may be in image pixels; the spacing is doubled until they fit
+const MIN_GRID_PIXELS: f64 = 48.0;
+
+const GRID_LINE: Rgba<u8> = Rgba([255, 200, 0, 140]);
+const GRID_LABEL: Rgba<u8> = Rgba([0, 0, 0, 255]);
+const GRID_LABEL_BACKGROUND: Rgba<u8> = Rgba([255, 200, 0, 255]);
+const WINDOW_LABEL: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+/// Outline colours, cycled through so that neighbouring windows are told apart
+const WINDOW_COLORS: [Rgba<u8>; 6] = [
+    Rgba([230, 25, 75, 255]),
+    Rgba([60, 180, 75, 255]),
+    Rgba([0, 130, 200, 255]),
+    Rgba([245, 130, 48, 255]),
+    Rgba([145, 30, 180, 255]),
+    Rgba([0, 150, 150, 255]),
+];
+
+/// Overlay drawn on top of a screenshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Annotation {
+    /// Grid lines labelled with their desktop coordinates
+    Grid,
+    /// Outline of each visible window, labelled with its ID and title
+    Windows,
+}
+
+/// A window outlined in an annotated image
+#[derive(Debug, Clone, Serialize)]
+pub struct OutlinedWindow {
+    pub window_id: u32,
+    pub title: String,
+    /// Visible part of the window in image pixels
+    pub rect: Rect,
+}
+
+/// An annotated copy of a capture
+pub struct AnnotatedImage {
+    pub image: RgbaImage,
+    /// Grid spacing actually used, in desktop units
+    pub grid_spacing: Option<u32>,
+    pub windows: Vec<OutlinedWindow>,
+}
+
+/// Draw a coordinate grid and/or window outlines on a copy of a capture
+///
+/// Grid labels and window geometry are in desktop coordinates, the same coordinates the
+/// input tools take, and are placed in the image through the capture's transform.
+///
+/// # Arguments
+/// * `capture` - The capture to annotate; its image is not modified
+/// * `annotations` - Which overlays to draw
+/// * `grid_spacing` - Distance between grid lines in desktop units; widened if the lines
+///   would be too close together in the image
+///
+/// # Returns
+/// * `Ok(AnnotatedImage)` - The annotated image and the windows that were outlined
+/// * `Err(String)` - Error message if the window list could not be read
+pub fn annotate(
+    capture: &Capture,
+    annotations: &[Annotation],
+    grid_spacing: u32,
+) -> Result<AnnotatedImage, String> {
+    let mut annotated = AnnotatedImage {
+        image: capture.image.clone(),
+        grid_spacing: None,
+        windows: Vec::new(),
+    };
+
+    // Outlines first, so that grid labels stay readable on top of them
+    if annotations.contains(&Annotation::Windows) {
+        annotated.windows = draw_windows(&mut annotated.image, capture)?;
+    }
+    if annotations.contains(&Annotation::Grid) {
+        annotated.grid_spacing = Some(draw_grid(
+            &mut annotated.image,
+            &capture.transform,
+            grid_spacing.max(1),
+        ));
+    }
+    Ok(annotated)
+}
+
+/// Draw grid lines every `spacing` desktop units, returning the spacing used
+fn draw_grid(image: &mut RgbaImage, transform: &CaptureTransform, spacing: u32) -> u32 {
+    let spacing = widen_spacing(spacing, transform.scale_x.min(transform.scale_y));
+
+    let (width, height) = image.dimensions();
+    let (left, top) = transform.to_desktop(0.0, 0.0);
+    let (right, bottom) = transform.to_desktop(width as f64, height as f64);
+    // (desktop coordinate, image pixel) of each line
+    let columns: Vec<(i64, i32)> = grid_lines(left, right, spacing)
+        .map(|x| (x, transform.to_pixel(x as f64, 0.0).0.round() as i32))
+        .collect();
+    let rows: Vec<(i64, i32)> = grid_lines(top, bottom, spacing)
+        .map(|y| (y, transform.to_pixel(0.0, y as f64).1.round() as i32))
+        .collect();
+
+    for &(_, pixel_x) in &columns {
+        for y in 0..height as i32 {
+            blend_pixel(image, pixel_x, y, GRID_LINE);
+        }
+    }
+    for &(_, pixel_y) in &rows {
+        for x in 0..width as i32 {
+            blend_pixel(image, x, pixel_y, GRID_LINE);
+        }
+    }
+
+    // Labels after all lines, so that no line crosses a label
+    for &(x, pixel_x) in &columns {
+        let label = x.to_string();
+        drawing::draw_label(
+            image,
+            pixel_x + 2,
+            0,
+            &label,
+            GRID_LABEL,
+            GRID_LABEL_BACKGROUND,
+            1,
+        );
+    }
+    for &(y, pixel_y) in &rows {
+        let label = y.to_string();
+        drawing::draw_label(
+            image,
+            0,
+            pixel_y + 2,
+            &label,
+            GRID_LABEL,
+            GRID_LABEL_BACKGROUND,
+            1,
+        );
+    }
+
+    spacing
+}
+
+/// Double `spacing` until lines are at least `MIN_GRID_PIXELS` apart, saturating at
+/// `u32::MAX`; a degenerate scale leaves the spacing unchanged
+fn widen_spacing(spacing: u32, pixels_per_unit: f64) -> u32 {
+    let pixels = spacing as f64 * pixels_per_unit;
+    if !(pixels > 0.0 && pixels.is_finite()) {
+        return spacing;
+    }
+    let doublings = (MIN_GRID_PIXELS / pixels).log2().ceil().max(0.0);
+    // Float to integer casts saturate
+    (spacing as f64 * doublings.exp2()) as u32
+}
+
+/// Multiples of `spacing` from `start` (inclusive) to `end` (exclusive)
+fn grid_lines(start: f64, end: f64, spacing: u32) -> impl Iterator<Item = i64> {
+    let step = spacing as i64;
+    let first = (start / step as f64).ceil() as i64 * step;
+    (0..)
+        .map(move |index| first + index * step)
+        .take_while(move |line| (*line as f64) < end)
+}
+
+/// Outline the windows visible in a capture, bottom-most first so top-most labels win
+fn draw_windows(image: &mut RgbaImage, capture: &Capture) -> Result<Vec<OutlinedWindow>, String> {
+    let bounds = Rect::new(0, 0, image.width(), image.height());
+    let captured_window = match capture.source {
+        CaptureSource::Window { window_id } => Some(window_id),
+        _ => None,
+    };
+
+    let mut windows = window_ops::list_windows()?;
+    windows.retain(|info| {
+        Some(info.id) != captured_window
+            && info.is_visible()
+            && WindowType::DEFAULT_FILTER.contains(&info.window_type.unwrap_or(WindowType::Normal))
+    });
+
+    let mut outlined = Vec::new();
+    for (index, info) in windows.iter().enumerate().rev() {
+        let (x, y) = capture.transform.to_pixel(info.x as f64, info.y as f64);
+        let (right, bottom) = capture.transform.to_pixel(
+            info.x as f64 + info.width as f64,
+            info.y as f64 + info.height as f64,
+        );
+        let rect = Rect::new(
+            x.round() as i32,
+            y.round() as i32,
+            (right - x).round().max(0.0) as u32,
+            (bottom - y).round().max(0.0) as u32,
+        );
+        let Some(visible) = rect.intersection(&bounds) else {
+            continue;
+        };
+
+        let color = WINDOW_COLORS[index % WINDOW_COLORS.len()];
+        // Draw inside the window so that outlines of maximized windows stay in the image
+        let thickness = 2;
+        if visible.width > 2 * thickness && visible.height > 2 * thickness {
+            let inner = Rect::new(
+                visible.x + thickness as i32,
+                visible.y + thickness as i32,
+                visible.width - 2 * thickness,
+                visible.height - 2 * thickness,
+            );
+            drawing::draw_outline(image, &inner, color, thickness);
+        }
+
+        let label = drawing::fit_text(
+            &format!("{} {}", info.id, info.title),
+            visible.width.saturating_sub(2 * thickness + 2),
+            1,
+        );
+        if !label.is_empty() && visible.height > GLYPH_HEIGHT + 2 * thickness {
+            drawing::draw_label(
+                image,
+                visible.x + thickness as i32,
+                visible.y + thickness as i32,
+                &label,
+                WINDOW_LABEL,
+                color,
+                1,
+            );
+        }
+
+        outlined.push(OutlinedWindow {
+            window_id: info.id,
+            title: info.title.clone(),
+            rect: visible,
+        });
+    }
+
+    // Report top-most first, like the window list
+    outlined.reverse();
+    Ok(outlined)
+}
+
+/// Alpha-blend `color` onto a pixel, ignoring pixels outside the image
+fn blend_pixel(image: &mut RgbaImage, x: i32, y: i32, color: Rgba<u8>) {
+    if x < 0 || y < 0 || x >= image.width() as i32 || y >= image.height() as i32 {
+        return;
+    }
+    let alpha = color.0[3] as u32;
+    let pixel = image.get_pixel_mut(x as u32, y as u32);
+    for channel in 0..3 {
+        let blended =
+            (color.0[channel] as u32 * alpha + pixel.0[channel] as u32 * (255 - alpha)) / 255;
+        pixel.0[channel] = blended as u8;
+    }
+}
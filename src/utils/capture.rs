@@ -0,0 +1,381 @@
+use image::imageops::FilterType;
+use image::RgbaImage;
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::time::{SystemTime, UNIX_EPOCH};
+use xcap::Monitor;
+
+use super::coordinates::CaptureTransform;
+use super::geometry::Rect;
+use super::recording::RecordingFormat;
+use super::window_ops;
+
+/// What a capture shows, so that it can be taken again
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CaptureSource {
+    /// A monitor by index, or the primary monitor
+    Monitor {
+        index: Option<usize>,
+    },
+    Window {
+        window_id: u32,
+    },
+    /// A rectangle in desktop coordinates
+    Region {
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+    },
+    /// A contact sheet of window thumbnails, which has no place on the desktop
+    ContactSheet,
+}
+
+impl CaptureSource {
+    /// Capture the same source again
+    pub fn capture(self) -> Result<Capture, String> {
+        match self {
+            CaptureSource::Monitor { index } => capture_monitor(index),
+            CaptureSource::Window { window_id } => capture_window(window_id),
+            CaptureSource::Region {
+                x,
+                y,
+                width,
+                height,
+            } => capture_region(x, y, width, height),
+            CaptureSource::ContactSheet => Err(
+                "A contact sheet cannot be captured again; call get_window_thumbnails".to_string(),
+            ),
+        }
+    }
+}
+
+/// A captured image together with a human readable description of its source
+pub struct Capture {
+    pub metadata: String,
+    pub image: RgbaImage,
+    /// Mapping from image pixels to desktop coordinates
+    pub transform: CaptureTransform,
+    pub source: CaptureSource,
+    pub captured_at: SystemTime,
+    /// Application and title of a captured window
+    pub app_name: Option<String>,
+    pub title: Option<String>,
+    /// Encoded animation of a recording, whose last frame is `image`
+    pub animation: Option<CaptureAnimation>,
+    /// The image as read from the screen, kept when overlays such as the cursor or
+    /// annotations were drawn onto `image`
+    pub raw_image: Option<RgbaImage>,
+}
+
+/// An animation stored with a capture
+pub struct CaptureAnimation {
+    pub format: RecordingFormat,
+    pub data: Vec<u8>,
+}
+
+impl Capture {
+    /// Build a capture of `region`, appending the pixel transform to the metadata
+    fn new(source: CaptureSource, description: String, image: RgbaImage, region: Rect) -> Self {
+        let transform = CaptureTransform::from_capture(region, image.width(), image.height());
+        let metadata = format!(
+            "{}\nTransform: {}",
+            description,
+            serde_json::to_string(&transform).unwrap_or_default()
+        );
+        Self {
+            metadata,
+            image,
+            transform,
+            source,
+            captured_at: SystemTime::now(),
+            app_name: None,
+            title: None,
+            animation: None,
+            raw_image: None,
+        }
+    }
+
+    /// Build a capture of an image composed by the server rather than read from the screen
+    ///
+    /// Its pixels do not map to the desktop, so the transform is the identity and the
+    /// metadata is just the description.
+    pub fn composed(source: CaptureSource, description: String, image: RgbaImage) -> Self {
+        Self {
+            metadata: description,
+            image,
+            transform: CaptureTransform::IDENTITY,
+            source,
+            captured_at: SystemTime::now(),
+            app_name: None,
+            title: None,
+            animation: None,
+            raw_image: None,
+        }
+    }
+
+    /// A copy of this capture with a processed image of the same size, e.g. annotated
+    ///
+    /// `note` is added to the metadata; the source, transform, time and raw image are kept.
+    pub fn derived(&self, image: RgbaImage, note: &str) -> Self {
+        Self {
+            metadata: format!("{}\n{}", self.metadata, note),
+            image,
+            transform: self.transform,
+            source: self.source,
+            captured_at: self.captured_at,
+            app_name: self.app_name.clone(),
+            title: self.title.clone(),
+            animation: None,
+            raw_image: Some(self.raw().clone()),
+        }
+    }
+
+    /// The image without overlays, for comparing captures with each other
+    pub fn raw(&self) -> &RgbaImage {
+        self.raw_image.as_ref().unwrap_or(&self.image)
+    }
+
+    /// Keep a copy of the image before an overlay is drawn onto it
+    pub fn keep_raw(&mut self) {
+        if self.raw_image.is_none() {
+            self.raw_image = Some(self.image.clone());
+        }
+    }
+
+    /// Capture time in milliseconds since the Unix epoch
+    pub fn captured_at_ms(&self) -> u64 {
+        self.captured_at
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or(0)
+    }
+
+    /// Attach the encoded animation of a recording that ended with this capture
+    ///
+    /// `note` is added to the metadata. The image stays the last frame, so tools that
+    /// work on still images treat the recording as its final state.
+    pub fn with_animation(self, format: RecordingFormat, data: Vec<u8>, note: &str) -> Self {
+        Self {
+            metadata: format!("{}\n{}", self.metadata, note),
+            animation: Some(CaptureAnimation { format, data }),
+            ..self
+        }
+    }
+
+    /// MIME type of the delivered image: the animation format for recordings, else PNG
+    pub fn mime_type(&self) -> &'static str {
+        self.animation
+            .as_ref()
+            .map_or("image/png", |animation| animation.format.mime_type())
+    }
+
+    /// Encode the delivered image: the animation for recordings, else the image as PNG
+    pub fn encoded(&self) -> Result<Cow<'_, [u8]>, String> {
+        match &self.animation {
+            Some(animation) => Ok(Cow::Borrowed(&animation.data)),
+            None => encode_png(&self.image).map(Cow::Owned),
+        }
+    }
+
+    /// Encode the delivered image as Base64, see `encoded`
+    pub fn to_base64(&self) -> Result<String, String> {
+        Ok(base64::Engine::encode(
+            &base64::engine::general_purpose::STANDARD,
+            self.encoded()?,
+        ))
+    }
+}
+
+/// Encode an image as PNG bytes
+pub fn encode_png(image: &RgbaImage) -> Result<Vec<u8>, String> {
+    let mut buffer = Vec::new();
+    image
+        .write_to(
+            &mut std::io::Cursor::new(&mut buffer),
+            image::ImageFormat::Png,
+        )
+        .map_err(|e| format!("Image encoding failed: {}", e))?;
+    Ok(buffer)
+}
+
+/// Image format a stored capture can be returned in
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageEncoding {
+    /// Lossless PNG
+    #[default]
+    Png,
+    /// Lossy JPEG, much smaller for photos and video frames
+    Jpeg,
+}
+
+impl ImageEncoding {
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ImageEncoding::Png => "image/png",
+            ImageEncoding::Jpeg => "image/jpeg",
+        }
+    }
+}
+
+/// Encode an image in the given format
+///
+/// JPEG has no alpha channel, so the image is flattened to RGB first.
+pub fn encode_image(image: &RgbaImage, encoding: ImageEncoding) -> Result<Vec<u8>, String> {
+    match encoding {
+        ImageEncoding::Png => encode_png(image),
+        ImageEncoding::Jpeg => {
+            let rgb = image::DynamicImage::ImageRgba8(image.clone()).to_rgb8();
+            let mut buffer = Vec::new();
+            rgb.write_to(
+                &mut std::io::Cursor::new(&mut buffer),
+                image::ImageFormat::Jpeg,
+            )
+            .map_err(|e| format!("Image encoding failed: {}", e))?;
+            Ok(buffer)
+        }
+    }
+}
+
+/// Resize an image by `factor`, keeping at least one pixel in each dimension
+pub fn scale_image(image: &RgbaImage, factor: f64) -> RgbaImage {
+    let width = ((image.width() as f64 * factor).round() as u32).max(1);
+    let height = ((image.height() as f64 * factor).round() as u32).max(1);
+    image::imageops::resize(image, width, height, FilterType::Triangle)
+}
+
+/// Encode an image as a Base64 PNG
+pub fn encode_png_base64(image: &RgbaImage) -> Result<String, String> {
+    let buffer = encode_png(image)?;
+    Ok(base64::Engine::encode(
+        &base64::engine::general_purpose::STANDARD,
+        buffer,
+    ))
+}
+
+/// Pick a monitor by index, or the primary monitor if no index is given
+pub fn select_monitor(monitors: &[Monitor], index: Option<usize>) -> Result<&Monitor, String> {
+    if monitors.is_empty() {
+        return Err("No monitors available".to_string());
+    }
+
+    if let Some(idx) = index {
+        monitors
+            .get(idx)
+            .ok_or_else(|| format!("Monitor index {} does not exist", idx))
+    } else {
+        monitors
+            .iter()
+            .find(|m| m.is_primary().unwrap_or(false))
+            .or_else(|| monitors.first())
+            .ok_or_else(|| "Unable to find primary monitor".to_string())
+    }
+}
+
+/// Capture a monitor by index, or the primary monitor if no index is given
+pub fn capture_monitor(index: Option<usize>) -> Result<Capture, String> {
+    let monitors = Monitor::all().map_err(|e| format!("Failed to get monitors: {}", e))?;
+    let monitor = select_monitor(&monitors, index)?;
+
+    let image = monitor
+        .capture_image()
+        .map_err(|e| format!("Screenshot failed: {}", e))?;
+
+    let metadata = format!(
+        "Monitor: {} (Index: {}, Size: {}x{})",
+        monitor.name().unwrap_or_default(),
+        index.unwrap_or(0),
+        monitor.width().unwrap_or(0),
+        monitor.height().unwrap_or(0)
+    );
+    let region = Rect::new(
+        monitor.x().unwrap_or(0),
+        monitor.y().unwrap_or(0),
+        monitor.width().unwrap_or(0),
+        monitor.height().unwrap_or(0),
+    );
+
+    Ok(Capture::new(
+        CaptureSource::Monitor { index },
+        metadata,
+        image,
+        region,
+    ))
+}
+
+/// Capture a window by ID
+///
+/// Windows on a workspace other than the current one are refused, since their image would
+/// be blank or stale.
+pub fn capture_window(window_id: u32) -> Result<Capture, String> {
+    let window = window_ops::find_window(window_id)?;
+    window_ops::ensure_on_current_workspace(window_id)?;
+
+    let image = window
+        .capture_image()
+        .map_err(|e| format!("Window screenshot failed: {}", e))?;
+
+    let metadata = format!(
+        "Window: {} [{}] (ID: {}, Size: {}x{})",
+        window.title().unwrap_or_default(),
+        window.app_name().unwrap_or_default(),
+        window.id().unwrap_or(0),
+        window.width().unwrap_or(0),
+        window.height().unwrap_or(0)
+    );
+    let region = Rect::new(
+        window.x().unwrap_or(0),
+        window.y().unwrap_or(0),
+        window.width().unwrap_or(0),
+        window.height().unwrap_or(0),
+    );
+
+    let mut capture = Capture::new(CaptureSource::Window { window_id }, metadata, image, region);
+    capture.app_name = window.app_name().ok();
+    capture.title = window.title().ok();
+    Ok(capture)
+}
+
+/// Capture a rectangle given in desktop coordinates
+///
+/// The region must lie on a single monitor, the one containing its top-left corner.
+pub fn capture_region(x: i32, y: i32, width: u32, height: u32) -> Result<Capture, String> {
+    if width == 0 || height == 0 {
+        return Err("Region width and height must be greater than zero".to_string());
+    }
+
+    let monitor = Monitor::from_point(x, y)
+        .map_err(|e| format!("No monitor contains point ({}, {}): {}", x, y, e))?;
+
+    let local_x = (x - monitor.x().unwrap_or(0)) as u32;
+    let local_y = (y - monitor.y().unwrap_or(0)) as u32;
+    let image = monitor
+        .capture_region(local_x, local_y, width, height)
+        .map_err(|e| format!("Region screenshot failed: {}", e))?;
+
+    let metadata = format!(
+        "Region: ({}, {}) {}x{} on monitor {}",
+        x,
+        y,
+        width,
+        height,
+        monitor.name().unwrap_or_default()
+    );
+
+    Ok(Capture::new(
+        CaptureSource::Region {
+            x,
+            y,
+            width,
+            height,
+        },
+        metadata,
+        image,
+        Rect::new(x, y, width, height),
+    ))
+}
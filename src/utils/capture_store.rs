@@ -0,0 +1,412 @@
+use serde::Serialize;
+use std::collections::hash_map::RandomState;
+use std::collections::VecDeque;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::capture::{Capture, CaptureSource};
+
+/// Number of captures kept when no limit is configured
+pub const DEFAULT_MAX_CAPTURES: usize = 32;
+
+/// Decoded image memory kept when no limit is configured, in MiB
+pub const DEFAULT_MAX_MEGABYTES: usize = 256;
+
+/// URI scheme under which stored captures are exposed as MCP resources
+pub const CAPTURE_URI_SCHEME: &str = "capture://";
+
+/// Resource URI of a stored capture
+pub fn capture_uri(id: u64) -> String {
+    format!("{}{}", CAPTURE_URI_SCHEME, id)
+}
+
+/// Capture ID from a `capture://{id}` resource URI
+pub fn parse_capture_uri(uri: &str) -> Option<u64> {
+    uri.strip_prefix(CAPTURE_URI_SCHEME)?.parse().ok()
+}
+
+/// Path under which a stored capture is served in HTTP mode
+pub fn capture_http_path(id: u64, token: &str) -> String {
+    format!("/captures/{}/{}", id, token)
+}
+
+/// How many captures the store keeps, by count and by decoded image size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureLimits {
+    pub max_captures: usize,
+    /// Total size of the stored RGBA images in bytes
+    pub max_bytes: usize,
+}
+
+impl Default for CaptureLimits {
+    fn default() -> Self {
+        Self {
+            max_captures: DEFAULT_MAX_CAPTURES,
+            max_bytes: DEFAULT_MAX_MEGABYTES * 1024 * 1024,
+        }
+    }
+}
+
+/// Recent captures kept in memory so that later tools can refer to them by ID
+///
+/// The oldest captures are dropped once either limit is exceeded, but the newest capture
+/// is always kept. One store, and its limits, is shared by every session of a server, but
+/// each session only sees its own captures through its `SessionCaptures`.
+pub struct CaptureStore {
+    limits: CaptureLimits,
+    /// Scheme and authority captures are served under, once HTTP serving has started
+    http_base_url: OnceLock<String>,
+    inner: Mutex<StoreInner>,
+}
+
+struct StoreInner {
+    next_id: u64,
+    next_session: u64,
+    bytes: usize,
+    captures: VecDeque<StoredCapture>,
+}
+
+struct StoredCapture {
+    id: u64,
+    session: u64,
+    /// Secret that grants access to the capture over HTTP
+    token: String,
+    capture: Arc<Capture>,
+}
+
+/// The captures of one session, in a store shared with other sessions
+#[derive(Clone)]
+pub struct SessionCaptures {
+    store: Arc<CaptureStore>,
+    session: u64,
+}
+
+/// Description of a stored capture, without its image
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptureSummary {
+    pub id: u64,
+    /// Path the image is served under in HTTP mode
+    pub http_path: String,
+    /// Milliseconds since the Unix epoch
+    pub captured_at_ms: u64,
+    pub source: CaptureSource,
+    pub width: u32,
+    pub height: u32,
+    /// MIME type the capture is delivered as, `image/gif` or `image/png` for recordings
+    pub mime_type: &'static str,
+    pub metadata: String,
+}
+
+impl Default for CaptureStore {
+    fn default() -> Self {
+        Self::new(CaptureLimits::default())
+    }
+}
+
+impl CaptureStore {
+    pub fn new(limits: CaptureLimits) -> Self {
+        Self {
+            limits: CaptureLimits {
+                max_captures: limits.max_captures.max(1),
+                ..limits
+            },
+            http_base_url: OnceLock::new(),
+            inner: Mutex::new(StoreInner {
+                next_id: 1,
+                next_session: 1,
+                bytes: 0,
+                captures: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Start a new session, which sees only the captures it stores itself
+    pub fn session(self: &Arc<Self>) -> SessionCaptures {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let session = inner.next_session;
+        inner.next_session += 1;
+        SessionCaptures {
+            store: self.clone(),
+            session,
+        }
+    }
+
+    /// Look up a capture by ID and HTTP access token, regardless of its session
+    pub fn get_with_token(&self, id: u64, token: &str) -> Option<Arc<Capture>> {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner
+            .captures
+            .iter()
+            .find(|stored| stored.id == id && tokens_equal(&stored.token, token))
+            .map(|stored| stored.capture.clone())
+    }
+
+    pub fn limits(&self) -> CaptureLimits {
+        self.limits
+    }
+
+    /// Record that captures are served over HTTP under `base_url`, e.g. `http://host:8080`
+    ///
+    /// Capture results then carry the full URL of each capture. Only the first call counts.
+    pub fn set_http_base_url(&self, base_url: String) {
+        let _ = self.http_base_url.set(base_url);
+    }
+}
+
+impl SessionCaptures {
+    /// Store a capture and return its ID
+    pub fn insert(&self, capture: Arc<Capture>) -> u64 {
+        let limits = self.store.limits;
+        let mut inner = self.store.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.bytes += image_bytes(&capture);
+        inner.captures.push_back(StoredCapture {
+            id,
+            session: self.session,
+            token: new_token(id),
+            capture,
+        });
+        while inner.captures.len() > 1
+            && (inner.captures.len() > limits.max_captures || inner.bytes > limits.max_bytes)
+        {
+            if let Some(evicted) = inner.captures.pop_front() {
+                inner.bytes -= image_bytes(&evicted.capture);
+            }
+        }
+        id
+    }
+
+    /// Look up a capture of this session by ID
+    pub fn get(&self, id: u64) -> Option<Arc<Capture>> {
+        let inner = self.store.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner
+            .captures
+            .iter()
+            .find(|stored| stored.id == id && stored.session == self.session)
+            .map(|stored| stored.capture.clone())
+    }
+
+    /// Look up a capture by ID, with an error naming the ID if it is unknown or evicted
+    pub fn require(&self, id: u64) -> Result<Arc<Capture>, String> {
+        self.get(id)
+            .ok_or_else(|| format!("Capture ID {} does not exist or has expired", id))
+    }
+
+    /// Full HTTP URL of a capture of this session, if captures are served over HTTP
+    pub fn http_url(&self, id: u64) -> Option<String> {
+        let base_url = self.store.http_base_url.get()?;
+        let inner = self.store.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner
+            .captures
+            .iter()
+            .find(|stored| stored.id == id && stored.session == self.session)
+            .map(|stored| format!("{}{}", base_url, capture_http_path(id, &stored.token)))
+    }
+
+    /// Describe the captures of this session, newest first
+    pub fn list(&self) -> Vec<CaptureSummary> {
+        let inner = self.store.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner
+            .captures
+            .iter()
+            .rev()
+            .filter(|stored| stored.session == self.session)
+            .map(|stored| CaptureSummary {
+                id: stored.id,
+                http_path: capture_http_path(stored.id, &stored.token),
+                captured_at_ms: stored.capture.captured_at_ms(),
+                source: stored.capture.source,
+                width: stored.capture.image.width(),
+                height: stored.capture.image.height(),
+                mime_type: stored.capture.mime_type(),
+                metadata: stored.capture.metadata.clone(),
+            })
+            .collect()
+    }
+
+    /// Number of captures of this session and the total size of their images in bytes
+    pub fn usage(&self) -> (usize, usize) {
+        let inner = self.store.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner
+            .captures
+            .iter()
+            .filter(|stored| stored.session == self.session)
+            .fold((0, 0), |(count, bytes), stored| {
+                (count + 1, bytes + image_bytes(&stored.capture))
+            })
+    }
+
+    /// Limits of the whole store, which all sessions share
+    pub fn limits(&self) -> CaptureLimits {
+        self.store.limits
+    }
+}
+
+fn image_bytes(capture: &Capture) -> usize {
+    let animation = capture
+        .animation
+        .as_ref()
+        .map_or(0, |animation| animation.data.len());
+    let raw = capture
+        .raw_image
+        .as_ref()
+        .map_or(0, |image| image.as_raw().len());
+    capture.image.as_raw().len() + raw + animation
+}
+
+/// An unguessable 128-bit hex token
+///
+/// `RandomState` keys SipHash with OS randomness, so its output cannot be predicted
+/// without the key; every `RandomState` gets a different key.
+fn new_token(id: u64) -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default();
+    let half = || {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(id);
+        hasher.write_u64(nanos);
+        hasher.finish()
+    };
+    format!("{:016x}{:016x}", half(), half())
+}
+
+/// Compare tokens without stopping at the first differing byte
+fn tokens_equal(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::recording::RecordingFormat;
+    use image::RgbaImage;
+
+    fn capture(width: u32, height: u32) -> Arc<Capture> {
+        Arc::new(Capture::composed(
+            CaptureSource::ContactSheet,
+            "test".to_string(),
+            RgbaImage::new(width, height),
+        ))
+    }
+
+    fn store(max_captures: usize, max_bytes: usize) -> Arc<CaptureStore> {
+        Arc::new(CaptureStore::new(CaptureLimits {
+            max_captures,
+            max_bytes,
+        }))
+    }
+
+    #[test]
+    fn evicts_oldest_beyond_max_captures() {
+        let session = store(2, usize::MAX).session();
+        let first = session.insert(capture(1, 1));
+        let second = session.insert(capture(1, 1));
+        let third = session.insert(capture(1, 1));
+        assert!(session.get(first).is_none());
+        assert!(session.get(second).is_some());
+        assert!(session.get(third).is_some());
+        assert_eq!(session.usage(), (2, 8));
+    }
+
+    #[test]
+    fn evicts_oldest_beyond_max_bytes_but_keeps_newest() {
+        // Each 10x10 capture takes 400 bytes
+        let session = store(10, 1000).session();
+        let ids: Vec<u64> = (0..3).map(|_| session.insert(capture(10, 10))).collect();
+        assert!(session.get(ids[0]).is_none());
+        assert_eq!(session.usage(), (2, 800));
+
+        let large = session.insert(capture(100, 100));
+        assert!(session.require(large).is_ok());
+        assert_eq!(session.usage(), (1, 40_000));
+    }
+
+    #[test]
+    fn sessions_only_see_their_own_captures() {
+        let store = store(10, usize::MAX);
+        let (a, b) = (store.session(), store.session());
+        let id = a.insert(capture(1, 1));
+        assert!(a.get(id).is_some());
+        assert!(b.get(id).is_none());
+        assert!(b.require(id).is_err());
+        assert!(b.list().is_empty());
+        assert_eq!(a.list().len(), 1);
+    }
+
+    #[test]
+    fn http_access_needs_the_capture_token() {
+        let store = store(10, usize::MAX);
+        let session = store.session();
+        let id = session.insert(capture(1, 1));
+        let path = session.list()[0].http_path.clone();
+        let token = path.rsplit('/').next().unwrap();
+        assert_eq!(path, capture_http_path(id, token));
+        assert_eq!(token.len(), 32);
+        assert!(store.get_with_token(id, token).is_some());
+        assert!(store.get_with_token(id, "0".repeat(32).as_str()).is_none());
+        assert!(store.get_with_token(id + 1, token).is_none());
+    }
+
+    #[test]
+    fn recordings_count_their_animation_and_report_its_type() {
+        let session = store(10, usize::MAX).session();
+        let recording = Capture::composed(
+            CaptureSource::ContactSheet,
+            "test".to_string(),
+            RgbaImage::new(1, 1),
+        )
+        .with_animation(RecordingFormat::Gif, vec![0; 100], "Recording");
+        session.insert(Arc::new(recording));
+        assert_eq!(session.usage(), (1, 104));
+        assert_eq!(session.list()[0].mime_type, "image/gif");
+    }
+
+    #[test]
+    fn overlaid_captures_keep_and_count_their_raw_image() {
+        let session = store(10, usize::MAX).session();
+        let mut capture = Capture::composed(
+            CaptureSource::ContactSheet,
+            "test".to_string(),
+            RgbaImage::new(2, 2),
+        );
+        capture.keep_raw();
+        capture.image.put_pixel(0, 0, image::Rgba([255, 0, 0, 255]));
+        let derived = capture.derived(RgbaImage::new(2, 2), "Annotated");
+        assert_eq!(capture.raw().get_pixel(0, 0).0, [0, 0, 0, 0]);
+        assert_eq!(derived.raw(), capture.raw());
+
+        session.insert(Arc::new(capture));
+        assert_eq!(session.usage(), (1, 32));
+    }
+
+    #[test]
+    fn http_urls_need_http_serving() {
+        let store = store(10, usize::MAX);
+        let session = store.session();
+        let id = session.insert(capture(1, 1));
+        assert_eq!(session.http_url(id), None);
+
+        store.set_http_base_url("http://127.0.0.1:8080".to_string());
+        let path = session.list()[0].http_path.clone();
+        assert_eq!(
+            session.http_url(id),
+            Some(format!("http://127.0.0.1:8080{}", path))
+        );
+        assert_eq!(store.session().http_url(id), None);
+    }
+
+    #[test]
+    fn capture_uris_round_trip() {
+        assert_eq!(parse_capture_uri(&capture_uri(42)), Some(42));
+        assert_eq!(parse_capture_uri("capture://abc"), None);
+        assert_eq!(parse_capture_uri("file:///42"), None);
+    }
+}
@@ -0,0 +1,105 @@
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use super::capture::{Capture, CaptureSource};
+use super::image_diff::diff_images;
+
+/// What `wait_for_change` waits for
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum WaitMode {
+    /// Return as soon as the content differs from the first capture
+    #[default]
+    Change,
+    /// Return once the content has stopped changing for `stable_ms`
+    Stable,
+}
+
+/// Options for `wait_for_change`
+#[derive(Debug, Clone, Copy)]
+pub struct WaitOptions {
+    pub mode: WaitMode,
+    /// Share of changed pixels (0.0 to 100.0) that must be exceeded to count as a change
+    pub threshold_percent: f64,
+    /// Largest per-channel difference still treated as unchanged
+    pub pixel_threshold: u8,
+    pub poll_interval: Duration,
+    pub timeout: Duration,
+    /// How long the content must stay unchanged in `Stable` mode
+    pub stable_for: Duration,
+}
+
+/// Result of waiting for a change
+#[derive(Debug, Clone, Serialize)]
+pub struct WaitReport {
+    pub mode: WaitMode,
+    /// Whether the condition was met before the timeout
+    pub satisfied: bool,
+    pub elapsed_ms: u64,
+    /// Number of captures taken, including the first
+    pub captures: u32,
+    /// Change between the last two compared captures, 0.0 to 100.0
+    pub last_change_percent: f64,
+}
+
+/// Capture a source repeatedly until its content changes or settles
+///
+/// # Arguments
+/// * `source` - The monitor, window or region to watch
+/// * `options` - Wait mode, thresholds, poll interval and timeout
+///
+/// # Returns
+/// * `Ok((WaitReport, Capture))` - Whether the condition was met, and the last capture
+/// * `Err(String)` - Error message if a capture failed
+pub fn wait_for_change(
+    source: CaptureSource,
+    options: WaitOptions,
+) -> Result<(WaitReport, Capture), String> {
+    let start = Instant::now();
+    // Change mode compares with the first capture, stable mode with the previous one
+    let mut reference = source.capture()?;
+    let mut latest: Option<Capture> = None;
+    let mut captures = 1;
+    let mut last_change_percent = 0.0;
+    let mut stable_since = Instant::now();
+
+    loop {
+        let satisfied = match options.mode {
+            WaitMode::Change => last_change_percent > options.threshold_percent,
+            WaitMode::Stable => stable_since.elapsed() >= options.stable_for,
+        };
+        if satisfied || start.elapsed() >= options.timeout {
+            let report = WaitReport {
+                mode: options.mode,
+                satisfied,
+                elapsed_ms: start.elapsed().as_millis() as u64,
+                captures,
+                last_change_percent,
+            };
+            return Ok((report, latest.unwrap_or(reference)));
+        }
+
+        std::thread::sleep(options.poll_interval);
+        let current = source.capture()?;
+        captures += 1;
+
+        // A size change (e.g. a resized window) counts as a complete change
+        last_change_percent =
+            diff_images(&reference.image, &current.image, options.pixel_threshold)
+                .map(|report| report.change_percent)
+                .unwrap_or(100.0);
+
+        match options.mode {
+            WaitMode::Change => latest = Some(current),
+            WaitMode::Stable => {
+                if last_change_percent > options.threshold_percent {
+                    stable_since = Instant::now();
+                }
+                reference = current;
+            }
+        }
+    }
+}
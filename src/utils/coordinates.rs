@@ -0,0 +1,285 @@
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+use xcap::{Monitor, Window};
+
+use super::capture::select_monitor;
+use super::geometry::Rect;
+use super::window_ops::find_window;
+
+/// A coordinate space that points can be converted between
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CoordinateSpace {
+    /// Desktop coordinates, as used by `get_window_list` and the input tools
+    Desktop,
+    /// Pixels of a monitor screenshot
+    Monitor,
+    /// Pixels of a window screenshot
+    Window,
+}
+
+/// Mapping between the pixels of a captured image and desktop coordinates
+///
+/// `desktop = origin + pixel / scale`. The scale combines the monitor's pixels per
+/// desktop unit with any resizing applied to the image after capture.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CaptureTransform {
+    /// Desktop position of the image's top-left pixel
+    pub origin_x: i32,
+    pub origin_y: i32,
+    /// Image pixels per desktop unit
+    pub scale_x: f64,
+    pub scale_y: f64,
+}
+
+impl CaptureTransform {
+    /// Desktop coordinates mapped onto themselves
+    pub const IDENTITY: Self = Self {
+        origin_x: 0,
+        origin_y: 0,
+        scale_x: 1.0,
+        scale_y: 1.0,
+    };
+
+    /// Transform of an image of `image_width`x`image_height` pixels showing `region`
+    pub fn from_capture(region: Rect, image_width: u32, image_height: u32) -> Self {
+        let ratio = |pixels: u32, units: u32| {
+            if units == 0 {
+                1.0
+            } else {
+                pixels as f64 / units as f64
+            }
+        };
+        Self {
+            origin_x: region.x,
+            origin_y: region.y,
+            scale_x: ratio(image_width, region.width),
+            scale_y: ratio(image_height, region.height),
+        }
+    }
+
+    /// Transform a capture of `region` would have, given the pixels per desktop unit
+    pub fn from_region(region: Rect, pixels_per_unit: f64) -> Self {
+        Self {
+            origin_x: region.x,
+            origin_y: region.y,
+            scale_x: pixels_per_unit,
+            scale_y: pixels_per_unit,
+        }
+    }
+
+    /// The same transform after resizing the image by `factor`
+    pub fn scaled(self, factor: f64) -> Self {
+        Self {
+            scale_x: self.scale_x * factor,
+            scale_y: self.scale_y * factor,
+            ..self
+        }
+    }
+
+    pub fn to_desktop(&self, pixel_x: f64, pixel_y: f64) -> (f64, f64) {
+        (
+            self.origin_x as f64 + pixel_x / self.scale_x,
+            self.origin_y as f64 + pixel_y / self.scale_y,
+        )
+    }
+
+    pub fn to_pixel(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            (x - self.origin_x as f64) * self.scale_x,
+            (y - self.origin_y as f64) * self.scale_y,
+        )
+    }
+}
+
+/// Pixels a capture of `monitor` has per desktop unit
+///
+/// macOS reports geometry in points, so captures carry `scale_factor` pixels per point.
+/// Windows and X11 report geometry in physical pixels.
+fn pixels_per_unit(monitor: &Monitor) -> f64 {
+    if cfg!(target_os = "macos") {
+        monitor.scale_factor().unwrap_or(1.0) as f64
+    } else {
+        1.0
+    }
+}
+
+fn monitor_rect(monitor: &Monitor) -> Rect {
+    Rect::new(
+        monitor.x().unwrap_or(0),
+        monitor.y().unwrap_or(0),
+        monitor.width().unwrap_or(0),
+        monitor.height().unwrap_or(0),
+    )
+}
+
+fn window_rect(window: &Window) -> Rect {
+    Rect::new(
+        window.x().unwrap_or(0),
+        window.y().unwrap_or(0),
+        window.width().unwrap_or(0),
+        window.height().unwrap_or(0),
+    )
+}
+
+/// Transform of a screenshot of a monitor by index, or the primary monitor
+pub fn monitor_transform(index: Option<usize>) -> Result<CaptureTransform, String> {
+    let monitors = Monitor::all().map_err(|e| format!("Failed to get monitors: {}", e))?;
+    let monitor = select_monitor(&monitors, index)?;
+    Ok(CaptureTransform::from_region(
+        monitor_rect(monitor),
+        pixels_per_unit(monitor),
+    ))
+}
+
+/// Transform of a screenshot of the monitor containing a desktop point
+pub fn monitor_transform_at(x: i32, y: i32) -> Result<CaptureTransform, String> {
+    let monitor = Monitor::from_point(x, y)
+        .map_err(|e| format!("No monitor contains point ({}, {}): {}", x, y, e))?;
+    Ok(CaptureTransform::from_region(
+        monitor_rect(&monitor),
+        pixels_per_unit(&monitor),
+    ))
+}
+
+/// Transform of a screenshot of a window
+pub fn window_transform(window_id: u32) -> Result<CaptureTransform, String> {
+    let window = find_window(window_id)?;
+    let pixels_per_unit = window
+        .current_monitor()
+        .map(|monitor| pixels_per_unit(&monitor))
+        .unwrap_or(1.0);
+    Ok(CaptureTransform::from_region(
+        window_rect(&window),
+        pixels_per_unit,
+    ))
+}
+
+/// A point converted between coordinate spaces
+#[derive(Debug, Clone, Serialize)]
+pub struct TransformedPoint {
+    pub x: f64,
+    pub y: f64,
+    pub space: CoordinateSpace,
+    /// The transforms applied, source first
+    pub transforms: Vec<CaptureTransform>,
+}
+
+/// Where a point is converted from or to
+#[derive(Debug, Clone, Copy)]
+pub struct SpaceRef {
+    pub space: CoordinateSpace,
+    /// Monitor index for `Monitor`; the primary monitor, or the monitor containing the
+    /// point when converting from desktop coordinates, if not given
+    pub monitor_index: Option<usize>,
+    /// Window ID, required for `Window`
+    pub window_id: Option<u32>,
+    /// Factor this side's screenshot was resized by after capture
+    pub capture_scale: f64,
+}
+
+/// Convert a point from one coordinate space to another via desktop coordinates
+///
+/// Each side uses its own monitor, window and capture scale, so a point can be mapped
+/// from a downscaled monitor screenshot to full-size window pixels.
+pub fn transform_point(
+    x: f64,
+    y: f64,
+    from: SpaceRef,
+    to: SpaceRef,
+) -> Result<TransformedPoint, String> {
+    let mut transforms = Vec::new();
+
+    let (desktop_x, desktop_y) = match from.space {
+        CoordinateSpace::Desktop => (x, y),
+        _ => {
+            let transform = space_transform(from, None)?;
+            transforms.push(transform);
+            transform.to_desktop(x, y)
+        }
+    };
+
+    let (x, y) = match to.space {
+        CoordinateSpace::Desktop => (desktop_x, desktop_y),
+        _ => {
+            let point = (desktop_x.floor() as i32, desktop_y.floor() as i32);
+            let transform = space_transform(to, Some(point))?;
+            transforms.push(transform);
+            transform.to_pixel(desktop_x, desktop_y)
+        }
+    };
+
+    Ok(TransformedPoint {
+        x,
+        y,
+        space: to.space,
+        transforms,
+    })
+}
+
+fn space_transform(
+    space: SpaceRef,
+    desktop_point: Option<(i32, i32)>,
+) -> Result<CaptureTransform, String> {
+    let transform = match space.space {
+        CoordinateSpace::Desktop => return Ok(CaptureTransform::IDENTITY),
+        CoordinateSpace::Monitor => match (space.monitor_index, desktop_point) {
+            (None, Some((x, y))) => monitor_transform_at(x, y)?,
+            (index, _) => monitor_transform(index)?,
+        },
+        CoordinateSpace::Window => {
+            let window_id = space
+                .window_id
+                .ok_or_else(|| "window_id is required for the window space".to_string())?;
+            window_transform(window_id)?
+        }
+    };
+    Ok(transform.scaled(space.capture_scale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: (f64, f64), expected: (f64, f64)) {
+        assert!(
+            (actual.0 - expected.0).abs() < 1e-9 && (actual.1 - expected.1).abs() < 1e-9,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn pixels_and_desktop_round_trip() {
+        let transform =
+            CaptureTransform::from_capture(Rect::new(-1920, 200, 1280, 720), 2560, 1440);
+        assert_eq!(transform.scale_x, 2.0);
+        assert_eq!(transform.scale_y, 2.0);
+        assert_close(transform.to_desktop(0.0, 0.0), (-1920.0, 200.0));
+        assert_close(transform.to_pixel(-1900.0, 210.0), (40.0, 20.0));
+        for (x, y) in [(0.0, 0.0), (123.5, 77.25), (2559.0, 1439.0)] {
+            let (dx, dy) = transform.to_desktop(x, y);
+            assert_close(transform.to_pixel(dx, dy), (x, y));
+        }
+    }
+
+    #[test]
+    fn scaled_transform_maps_resized_pixels_to_the_same_point() {
+        let transform = CaptureTransform::from_region(Rect::new(100, 50, 800, 600), 1.0);
+        let half = transform.scaled(0.5);
+        assert_close(
+            half.to_desktop(200.0, 100.0),
+            transform.to_desktop(400.0, 200.0),
+        );
+        assert_close(half.to_pixel(500.0, 350.0), (200.0, 150.0));
+    }
+
+    #[test]
+    fn empty_region_falls_back_to_unit_scale() {
+        let transform = CaptureTransform::from_capture(Rect::new(0, 0, 0, 0), 10, 10);
+        assert_eq!(transform.scale_x, 1.0);
+        assert_eq!(transform.scale_y, 1.0);
+        assert_close(CaptureTransform::IDENTITY.to_pixel(3.0, 4.0), (3.0, 4.0));
+    }
+}
@@ -0,0 +1,194 @@
+use serde::Serialize;
+
+use super::capture::Capture;
+use super::{monitor_ops, window_ops};
+
+#[cfg(target_os = "linux")]
+use super::x11_display::XDisplay;
+#[cfg(target_os = "linux")]
+use image::imageops::FilterType;
+#[cfg(target_os = "linux")]
+use image::{Rgba, RgbaImage};
+#[cfg(target_os = "linux")]
+use std::os::raw::{c_int, c_uint};
+#[cfg(target_os = "linux")]
+use x11::{xfixes, xlib};
+
+/// Where the mouse pointer is
+#[derive(Debug, Clone, Serialize)]
+pub struct CursorPosition {
+    /// Position in desktop coordinates
+    pub x: i32,
+    pub y: i32,
+    /// Monitor containing the pointer
+    pub monitor_index: Option<usize>,
+    /// Top-most visible window under the pointer
+    pub window_id: Option<u32>,
+    /// Mouse buttons currently held down (1 = left, 2 = middle, 3 = right)
+    pub buttons: Vec<u32>,
+}
+
+/// Get the pointer position, with the monitor and window under it
+///
+/// # Returns
+/// * `Ok(CursorPosition)` - The pointer position in desktop coordinates
+/// * `Err(String)` - Error message if the pointer could not be queried
+pub fn cursor_position() -> Result<CursorPosition, String> {
+    let (x, y, buttons) = query_pointer()?;
+    let monitor_index = monitor_ops::list_monitors()?
+        .iter()
+        .find(|monitor| monitor.rect().contains(x, y))
+        .map(|monitor| monitor.index);
+    let window_id = window_ops::window_at_point(x, y)?.map(|info| info.id);
+
+    Ok(CursorPosition {
+        x,
+        y,
+        monitor_index,
+        window_id,
+        buttons,
+    })
+}
+
+/// Draw the current cursor image onto a capture at the pointer position
+///
+/// The cursor is placed through the capture's transform, so it lands in the right spot
+/// for window captures and scaled images too. Its position is added to the metadata, and
+/// the image without the cursor is kept for diffs.
+///
+/// # Arguments
+/// * `capture` - The capture to draw on
+///
+/// # Returns
+/// * `Ok(())` - The cursor was drawn, or lies outside the capture
+/// * `Err(String)` - Error message if the cursor image is unavailable
+pub fn composite_cursor(capture: &mut Capture) -> Result<(), String> {
+    capture.keep_raw();
+    let (x, y) = draw_cursor_image(capture)?;
+    capture
+        .metadata
+        .push_str(&format!("\nCursor: ({}, {})", x, y));
+    Ok(())
+}
+
+/// Query the pointer position and held buttons via XQueryPointer
+#[cfg(target_os = "linux")]
+fn query_pointer() -> Result<(i32, i32, Vec<u32>), String> {
+    let display = XDisplay::open()?;
+    let mut root: xlib::Window = 0;
+    let mut child: xlib::Window = 0;
+    let (mut root_x, mut root_y, mut window_x, mut window_y): (c_int, c_int, c_int, c_int) =
+        (0, 0, 0, 0);
+    let mut mask: c_uint = 0;
+
+    let same_screen = unsafe {
+        xlib::XQueryPointer(
+            display.raw(),
+            display.root(),
+            &mut root,
+            &mut child,
+            &mut root_x,
+            &mut root_y,
+            &mut window_x,
+            &mut window_y,
+            &mut mask,
+        )
+    };
+    if same_screen == 0 {
+        return Err("The pointer is on another screen".to_string());
+    }
+
+    let buttons = [
+        (1, xlib::Button1Mask),
+        (2, xlib::Button2Mask),
+        (3, xlib::Button3Mask),
+        (4, xlib::Button4Mask),
+        (5, xlib::Button5Mask),
+    ]
+    .into_iter()
+    .filter(|(_, button_mask)| mask & button_mask != 0)
+    .map(|(button, _)| button)
+    .collect();
+    Ok((root_x, root_y, buttons))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn query_pointer() -> Result<(i32, i32, Vec<u32>), String> {
+    Err("Cursor position is only supported on Linux (X11)".to_string())
+}
+
+/// Overlay the XFixes cursor image, returning the pointer position
+#[cfg(target_os = "linux")]
+fn draw_cursor_image(capture: &mut Capture) -> Result<(i32, i32), String> {
+    let display = XDisplay::open()?;
+    let (mut event_base, mut error_base) = (0, 0);
+    if unsafe { xfixes::XFixesQueryExtension(display.raw(), &mut event_base, &mut error_base) } == 0
+    {
+        return Err("The X server does not support XFixes".to_string());
+    }
+
+    let cursor = unsafe { xfixes::XFixesGetCursorImage(display.raw()) };
+    if cursor.is_null() {
+        return Err("Failed to get the cursor image".to_string());
+    }
+    let (x, y, hot_x, hot_y, image) = unsafe {
+        let c = &*cursor;
+        // A hidden cursor may come without pixels
+        let image = if c.pixels.is_null() {
+            RgbaImage::new(0, 0)
+        } else {
+            let pixels = std::slice::from_raw_parts(c.pixels, c.width as usize * c.height as usize);
+            cursor_to_rgba(c.width as u32, c.height as u32, pixels)
+        };
+        let result = (c.x as i32, c.y as i32, c.xhot as i32, c.yhot as i32, image);
+        xlib::XFree(cursor as *mut _);
+        result
+    };
+
+    let transform = capture.transform;
+    if image.width() == 0 || image.height() == 0 {
+        return Ok((x, y));
+    }
+    let image = if (transform.scale_x - 1.0).abs() > f64::EPSILON
+        || (transform.scale_y - 1.0).abs() > f64::EPSILON
+    {
+        let width = ((image.width() as f64 * transform.scale_x).round() as u32).max(1);
+        let height = ((image.height() as f64 * transform.scale_y).round() as u32).max(1);
+        image::imageops::resize(&image, width, height, FilterType::Triangle)
+    } else {
+        image
+    };
+    let (left, top) = transform.to_pixel((x - hot_x) as f64, (y - hot_y) as f64);
+    image::imageops::overlay(
+        &mut capture.image,
+        &image,
+        left.round() as i64,
+        top.round() as i64,
+    );
+    Ok((x, y))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn draw_cursor_image(_capture: &mut Capture) -> Result<(i32, i32), String> {
+    Err("Including the cursor is only supported on Linux (X11)".to_string())
+}
+
+/// Convert XFixes cursor pixels (premultiplied ARGB, one per C long) to RGBA
+#[cfg(target_os = "linux")]
+fn cursor_to_rgba(width: u32, height: u32, pixels: &[std::os::raw::c_ulong]) -> RgbaImage {
+    RgbaImage::from_fn(width, height, |x, y| {
+        let argb = pixels[(y * width + x) as usize] as u32;
+        let alpha = argb >> 24;
+        let unpremultiply = |channel: u32| {
+            (channel * 255)
+                .checked_div(alpha)
+                .map_or(0, |value| value.min(255) as u8)
+        };
+        Rgba([
+            unpremultiply((argb >> 16) & 0xFF),
+            unpremultiply((argb >> 8) & 0xFF),
+            unpremultiply(argb & 0xFF),
+            alpha as u8,
+        ])
+    })
+}
@@ -0,0 +1,170 @@
+use serde::Serialize;
+
+/// Axis-aligned rectangle in desktop coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn right(&self) -> i32 {
+        self.x.saturating_add(self.width as i32)
+    }
+
+    pub fn bottom(&self) -> i32 {
+        self.y.saturating_add(self.height as i32)
+    }
+
+    pub fn area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && y >= self.y && x < self.right() && y < self.bottom()
+    }
+
+    /// The overlapping part of two rectangles, if any
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let left = self.x.max(other.x);
+        let top = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        if right <= left || bottom <= top {
+            return None;
+        }
+        Some(Rect::new(
+            left,
+            top,
+            (right - left) as u32,
+            (bottom - top) as u32,
+        ))
+    }
+
+    /// The parts of this rectangle not covered by `other`, as up to four disjoint rectangles
+    pub fn subtract(&self, other: &Rect) -> Vec<Rect> {
+        let Some(overlap) = self.intersection(other) else {
+            return vec![*self];
+        };
+
+        let mut pieces = Vec::with_capacity(4);
+        // Full-width bands above and below the overlap, then the sides within its rows
+        if overlap.y > self.y {
+            pieces.push(Rect::new(
+                self.x,
+                self.y,
+                self.width,
+                (overlap.y - self.y) as u32,
+            ));
+        }
+        if overlap.bottom() < self.bottom() {
+            pieces.push(Rect::new(
+                self.x,
+                overlap.bottom(),
+                self.width,
+                (self.bottom() - overlap.bottom()) as u32,
+            ));
+        }
+        if overlap.x > self.x {
+            pieces.push(Rect::new(
+                self.x,
+                overlap.y,
+                (overlap.x - self.x) as u32,
+                overlap.height,
+            ));
+        }
+        if overlap.right() < self.right() {
+            pieces.push(Rect::new(
+                overlap.right(),
+                overlap.y,
+                (self.right() - overlap.right()) as u32,
+                overlap.height,
+            ));
+        }
+        pieces
+    }
+}
+
+/// Remove every occluder from a set of disjoint rectangles
+pub fn subtract_all(mut region: Vec<Rect>, occluders: &[Rect]) -> Vec<Rect> {
+    for occluder in occluders {
+        if region.is_empty() {
+            break;
+        }
+        region = region
+            .iter()
+            .flat_map(|piece| piece.subtract(occluder))
+            .collect();
+    }
+    region
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total_area(rects: &[Rect]) -> u64 {
+        rects.iter().map(Rect::area).sum()
+    }
+
+    #[test]
+    fn intersection_of_disjoint_or_touching_rects_is_none() {
+        let a = Rect::new(0, 0, 10, 10);
+        assert_eq!(a.intersection(&Rect::new(20, 20, 5, 5)), None);
+        assert_eq!(a.intersection(&Rect::new(10, 0, 5, 5)), None);
+        assert_eq!(
+            a.intersection(&Rect::new(5, -5, 10, 10)),
+            Some(Rect::new(5, 0, 5, 5))
+        );
+    }
+
+    #[test]
+    fn subtract_without_overlap_keeps_the_rect() {
+        let a = Rect::new(0, 0, 10, 10);
+        assert_eq!(a.subtract(&Rect::new(10, 10, 5, 5)), vec![a]);
+    }
+
+    #[test]
+    fn subtract_hole_leaves_four_disjoint_pieces() {
+        let a = Rect::new(0, 0, 10, 10);
+        let pieces = a.subtract(&Rect::new(3, 3, 4, 4));
+        assert_eq!(pieces.len(), 4);
+        assert_eq!(total_area(&pieces), 100 - 16);
+        for (i, p) in pieces.iter().enumerate() {
+            assert!(pieces[i + 1..].iter().all(|q| p.intersection(q).is_none()));
+        }
+    }
+
+    #[test]
+    fn subtract_covering_rect_leaves_nothing() {
+        let a = Rect::new(5, 5, 10, 10);
+        assert!(a.subtract(&Rect::new(0, 0, 20, 20)).is_empty());
+    }
+
+    #[test]
+    fn subtract_all_computes_visible_area_of_occluded_window() {
+        let window = Rect::new(0, 0, 100, 100);
+        let above = [Rect::new(0, 0, 50, 100), Rect::new(40, 0, 20, 50)];
+        let visible = subtract_all(vec![window], &above);
+        // The right half stays, minus the 10x50 strip of the second occluder beyond x=50
+        assert_eq!(total_area(&visible), 50 * 100 - 10 * 50);
+
+        let fully = subtract_all(vec![window], &[Rect::new(-10, -10, 200, 200)]);
+        assert!(fully.is_empty());
+    }
+}
@@ -0,0 +1,268 @@
+use image::{Rgba, RgbaImage};
+use serde::Serialize;
+use std::collections::VecDeque;
+
+use super::drawing::draw_outline;
+use super::geometry::Rect;
+
+/// Side length of the cells changed pixels are grouped into, in pixels
+///
+/// Changes in neighbouring cells are merged into one region, so small gaps (e.g. between
+/// the letters of a changed label) do not split a region.
+const CELL_SIZE: u32 = 8;
+
+/// Regions listed in a diff report; the largest are kept
+const MAX_REGIONS: usize = 50;
+
+/// Colour used to outline changed regions in the highlight image
+const HIGHLIGHT: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+/// A connected area of changed pixels, in image pixel coordinates
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangedRegion {
+    #[serde(flatten)]
+    pub rect: Rect,
+    pub changed_pixels: u64,
+}
+
+/// Differences between two images of the same size
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffReport {
+    pub width: u32,
+    pub height: u32,
+    pub changed_pixels: u64,
+    /// Share of changed pixels, 0.0 to 100.0
+    pub change_percent: f64,
+    /// Total number of changed regions, which may exceed the regions listed
+    pub region_count: usize,
+    /// The largest changed regions, by changed pixel count
+    pub regions: Vec<ChangedRegion>,
+}
+
+impl DiffReport {
+    pub fn is_unchanged(&self) -> bool {
+        self.changed_pixels == 0
+    }
+}
+
+/// Per-cell tally of changed pixels and their bounding box
+#[derive(Clone, Copy)]
+struct Cell {
+    changed: u64,
+    min_x: u32,
+    min_y: u32,
+    max_x: u32,
+    max_y: u32,
+}
+
+/// Compare two images pixel by pixel
+///
+/// # Arguments
+/// * `before`, `after` - The images to compare; they must have the same size
+/// * `threshold` - Largest per-channel difference still treated as unchanged, to absorb
+///   compression noise and subpixel rendering
+///
+/// # Returns
+/// * `Ok(DiffReport)` - Changed pixel count, percentage and changed regions
+/// * `Err(String)` - Error message if the sizes differ
+pub fn diff_images(
+    before: &RgbaImage,
+    after: &RgbaImage,
+    threshold: u8,
+) -> Result<DiffReport, String> {
+    if before.dimensions() != after.dimensions() {
+        return Err(format!(
+            "Image sizes differ ({}x{} vs {}x{})",
+            before.width(),
+            before.height(),
+            after.width(),
+            after.height()
+        ));
+    }
+
+    let (width, height) = after.dimensions();
+    let columns = width.div_ceil(CELL_SIZE) as usize;
+    let rows = height.div_ceil(CELL_SIZE) as usize;
+    let mut cells: Vec<Option<Cell>> = vec![None; columns * rows];
+    let mut changed_pixels = 0u64;
+
+    for (x, y, pixel) in after.enumerate_pixels() {
+        let old = before.get_pixel(x, y);
+        let differs = pixel
+            .0
+            .iter()
+            .zip(old.0.iter())
+            .any(|(a, b)| a.abs_diff(*b) > threshold);
+        if !differs {
+            continue;
+        }
+
+        changed_pixels += 1;
+        let index = (y / CELL_SIZE) as usize * columns + (x / CELL_SIZE) as usize;
+        let cell = cells[index].get_or_insert(Cell {
+            changed: 0,
+            min_x: x,
+            min_y: y,
+            max_x: x,
+            max_y: y,
+        });
+        cell.changed += 1;
+        cell.min_x = cell.min_x.min(x);
+        cell.min_y = cell.min_y.min(y);
+        cell.max_x = cell.max_x.max(x);
+        cell.max_y = cell.max_y.max(y);
+    }
+
+    let mut regions = connected_regions(&mut cells, columns, rows);
+    let region_count = regions.len();
+    regions.sort_by_key(|region| std::cmp::Reverse(region.changed_pixels));
+    regions.truncate(MAX_REGIONS);
+
+    let total = width as u64 * height as u64;
+    Ok(DiffReport {
+        width,
+        height,
+        changed_pixels,
+        change_percent: if total == 0 {
+            0.0
+        } else {
+            changed_pixels as f64 * 100.0 / total as f64
+        },
+        region_count,
+        regions,
+    })
+}
+
+/// Merge changed cells that touch (including diagonally) into regions
+fn connected_regions(
+    cells: &mut [Option<Cell>],
+    columns: usize,
+    rows: usize,
+) -> Vec<ChangedRegion> {
+    let mut regions = Vec::new();
+    let mut queue = VecDeque::new();
+
+    for start in 0..cells.len() {
+        let Some(first) = cells[start].take() else {
+            continue;
+        };
+        let mut merged = first;
+        queue.push_back(start);
+
+        while let Some(index) = queue.pop_front() {
+            let (column, row) = (index % columns, index / columns);
+            for neighbour_row in row.saturating_sub(1)..=(row + 1).min(rows - 1) {
+                for neighbour_column in column.saturating_sub(1)..=(column + 1).min(columns - 1) {
+                    let neighbour = neighbour_row * columns + neighbour_column;
+                    if let Some(cell) = cells[neighbour].take() {
+                        merged.changed += cell.changed;
+                        merged.min_x = merged.min_x.min(cell.min_x);
+                        merged.min_y = merged.min_y.min(cell.min_y);
+                        merged.max_x = merged.max_x.max(cell.max_x);
+                        merged.max_y = merged.max_y.max(cell.max_y);
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+        }
+
+        regions.push(ChangedRegion {
+            rect: Rect::new(
+                merged.min_x as i32,
+                merged.min_y as i32,
+                merged.max_x - merged.min_x + 1,
+                merged.max_y - merged.min_y + 1,
+            ),
+            changed_pixels: merged.changed,
+        });
+    }
+
+    regions
+}
+
+/// Copy of `after` with unchanged areas dimmed and changed regions outlined
+pub fn highlight_changes(after: &RgbaImage, report: &DiffReport) -> RgbaImage {
+    let mut image = after.clone();
+    for pixel in image.pixels_mut() {
+        for channel in &mut pixel.0[..3] {
+            *channel /= 2;
+        }
+    }
+
+    for region in &report.regions {
+        let rect = region.rect;
+        // Restore the changed area at full brightness
+        for y in rect.y as u32..rect.bottom() as u32 {
+            for x in rect.x as u32..rect.right() as u32 {
+                image.put_pixel(x, y, *after.get_pixel(x, y));
+            }
+        }
+        draw_outline(&mut image, &rect, HIGHLIGHT, 2);
+    }
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+    const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+    fn with_block(base: &RgbaImage, x: u32, y: u32, size: u32) -> RgbaImage {
+        let mut image = base.clone();
+        for dy in 0..size {
+            for dx in 0..size {
+                image.put_pixel(x + dx, y + dy, BLACK);
+            }
+        }
+        image
+    }
+
+    #[test]
+    fn identical_images_are_unchanged() {
+        let image = RgbaImage::from_pixel(32, 32, WHITE);
+        let report = diff_images(&image, &image, 0).unwrap();
+        assert!(report.is_unchanged());
+        assert_eq!(report.region_count, 0);
+    }
+
+    #[test]
+    fn differences_within_threshold_are_ignored() {
+        let before = RgbaImage::from_pixel(16, 16, WHITE);
+        let after = RgbaImage::from_pixel(16, 16, Rgba([250, 255, 255, 255]));
+        assert!(diff_images(&before, &after, 8).unwrap().is_unchanged());
+        assert_eq!(diff_images(&before, &after, 4).unwrap().changed_pixels, 256);
+    }
+
+    #[test]
+    fn size_mismatch_is_an_error() {
+        let a = RgbaImage::new(10, 10);
+        let b = RgbaImage::new(10, 11);
+        assert!(diff_images(&a, &b, 0).is_err());
+    }
+
+    #[test]
+    fn separate_changes_form_separate_regions() {
+        let before = RgbaImage::from_pixel(64, 64, WHITE);
+        let after = with_block(&with_block(&before, 2, 2, 4), 50, 50, 6);
+        let report = diff_images(&before, &after, 0).unwrap();
+        assert_eq!(report.changed_pixels, 16 + 36);
+        assert_eq!(report.region_count, 2);
+        // Largest region first, with a tight bounding box
+        assert_eq!(report.regions[0].rect, Rect::new(50, 50, 6, 6));
+        assert_eq!(report.regions[0].changed_pixels, 36);
+        assert_eq!(report.regions[1].rect, Rect::new(2, 2, 4, 4));
+    }
+
+    #[test]
+    fn changes_in_neighbouring_cells_are_merged() {
+        let before = RgbaImage::from_pixel(64, 64, WHITE);
+        // Diagonally adjacent cells, with a gap between the changed pixels
+        let after = with_block(&with_block(&before, 5, 5, 2), 9, 9, 2);
+        let report = diff_images(&before, &after, 0).unwrap();
+        assert_eq!(report.region_count, 1);
+        assert_eq!(report.regions[0].rect, Rect::new(5, 5, 6, 6));
+        assert_eq!(report.regions[0].changed_pixels, 8);
+    }
+}
@@ -0,0 +1,602 @@
+use super::window_ops;
+use std::time::Duration;
+
+#[cfg(target_os = "linux")]
+use super::x11_display::XDisplay;
+#[cfg(target_os = "linux")]
+use std::cell::Cell;
+#[cfg(target_os = "linux")]
+use std::ffi::CString;
+#[cfg(target_os = "linux")]
+use x11::{xlib, xtest};
+
+/// Number of intermediate pointer positions generated for a drag
+const DRAG_STEPS: u32 = 20;
+
+/// Most clicks a single `mouse_click` may send
+pub const MAX_CLICKS: u32 = 10;
+
+/// Longest drag a single `mouse_drag` may take, in milliseconds
+pub const MAX_DRAG_DURATION_MS: u64 = 10_000;
+
+/// Most wheel notches a single `mouse_scroll` may send per axis
+pub const MAX_SCROLL_NOTCHES: u32 = 100;
+
+/// Delay between the clicks of a multi-click, short enough to register as a double click
+const CLICK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Time a temporary keycode binding is kept after the last key press
+#[cfg(target_os = "linux")]
+const SCRATCH_UNBIND_DELAY: Duration = Duration::from_millis(50);
+
+/// X11 button numbers used for scrolling
+const SCROLL_UP: u32 = 4;
+const SCROLL_DOWN: u32 = 5;
+const SCROLL_LEFT: u32 = 6;
+const SCROLL_RIGHT: u32 = 7;
+
+/// Translate a point to desktop coordinates
+///
+/// # Arguments
+/// * `x`, `y` - The point, relative to the window if `window_id` is given
+/// * `window_id` - Optional window the point is relative to
+///
+/// # Returns
+/// * `Ok((i32, i32))` - The point in desktop coordinates
+/// * `Err(String)` - Error message if the window does not exist
+pub fn resolve_point(x: i32, y: i32, window_id: Option<u32>) -> Result<(i32, i32), String> {
+    match window_id {
+        Some(window_id) => {
+            let window = window_ops::find_window(window_id)?;
+            Ok((x + window.x().unwrap_or(0), y + window.y().unwrap_or(0)))
+        }
+        None => Ok((x, y)),
+    }
+}
+
+/// Move the pointer to a desktop position
+pub fn mouse_move(x: i32, y: i32) -> Result<(), String> {
+    let input = InputDevice::open()?;
+    input.motion(x, y);
+    input.flush();
+    Ok(())
+}
+
+/// Click a mouse button, optionally moving to a desktop position first
+///
+/// # Arguments
+/// * `position` - Where to click, or `None` for the current pointer position
+/// * `button` - X11 button number (1 = left, 2 = middle, 3 = right)
+/// * `count` - Number of clicks, e.g. 2 for a double click; 1 to `MAX_CLICKS`
+pub fn mouse_click(position: Option<(i32, i32)>, button: u32, count: u32) -> Result<(), String> {
+    if count == 0 || count > MAX_CLICKS {
+        return Err(format!(
+            "Click count must be between 1 and {}, got {}",
+            MAX_CLICKS, count
+        ));
+    }
+    let input = InputDevice::open()?;
+    if let Some((x, y)) = position {
+        input.motion(x, y);
+    }
+    for i in 0..count {
+        if i > 0 {
+            std::thread::sleep(CLICK_INTERVAL);
+        }
+        input.button(button, true);
+        input.button(button, false);
+        input.flush();
+    }
+    Ok(())
+}
+
+/// Press a button at one desktop position, move to another and release it
+///
+/// # Arguments
+/// * `from`, `to` - Start and end positions in desktop coordinates
+/// * `button` - X11 button number to hold during the drag
+/// * `duration` - Total time spent moving between the two positions; at most
+///   `MAX_DRAG_DURATION_MS`
+pub fn mouse_drag(
+    from: (i32, i32),
+    to: (i32, i32),
+    button: u32,
+    duration: Duration,
+) -> Result<(), String> {
+    if duration > Duration::from_millis(MAX_DRAG_DURATION_MS) {
+        return Err(format!(
+            "Drag duration must be at most {} ms, got {} ms",
+            MAX_DRAG_DURATION_MS,
+            duration.as_millis()
+        ));
+    }
+    let input = InputDevice::open()?;
+    let step_delay = duration / DRAG_STEPS;
+
+    input.motion(from.0, from.1);
+    input.button(button, true);
+    input.flush();
+
+    // Intermediate motion events let applications recognise the gesture as a drag
+    for step in 1..=DRAG_STEPS {
+        std::thread::sleep(step_delay);
+        let t = step as f64 / DRAG_STEPS as f64;
+        let x = from.0 as f64 + (to.0 - from.0) as f64 * t;
+        let y = from.1 as f64 + (to.1 - from.1) as f64 * t;
+        input.motion(x.round() as i32, y.round() as i32);
+        input.flush();
+    }
+
+    input.button(button, false);
+    input.flush();
+    Ok(())
+}
+
+/// Scroll the mouse wheel, optionally moving to a desktop position first
+///
+/// # Arguments
+/// * `position` - Where to scroll, or `None` for the current pointer position
+/// * `delta_x` - Horizontal notches; positive scrolls right
+/// * `delta_y` - Vertical notches; positive scrolls down
+///
+/// Each delta must lie within `-MAX_SCROLL_NOTCHES..=MAX_SCROLL_NOTCHES`.
+pub fn mouse_scroll(
+    position: Option<(i32, i32)>,
+    delta_x: i32,
+    delta_y: i32,
+) -> Result<(), String> {
+    if delta_x.unsigned_abs() > MAX_SCROLL_NOTCHES || delta_y.unsigned_abs() > MAX_SCROLL_NOTCHES {
+        return Err(format!(
+            "Scroll deltas must be between -{} and {} notches, got ({}, {})",
+            MAX_SCROLL_NOTCHES, MAX_SCROLL_NOTCHES, delta_x, delta_y
+        ));
+    }
+    let input = InputDevice::open()?;
+    if let Some((x, y)) = position {
+        input.motion(x, y);
+    }
+
+    let vertical = if delta_y > 0 { SCROLL_DOWN } else { SCROLL_UP };
+    let horizontal = if delta_x > 0 {
+        SCROLL_RIGHT
+    } else {
+        SCROLL_LEFT
+    };
+    for (button, notches) in [(vertical, delta_y), (horizontal, delta_x)] {
+        for _ in 0..notches.unsigned_abs() {
+            input.button(button, true);
+            input.button(button, false);
+        }
+    }
+    input.flush();
+    Ok(())
+}
+
+/// Type a string character by character
+///
+/// # Arguments
+/// * `text` - The text to type; any Unicode character is supported
+/// * `delay` - Pause between characters
+pub fn type_text(text: &str, delay: Duration) -> Result<(), String> {
+    let input = InputDevice::open()?;
+    for (i, ch) in text.chars().enumerate() {
+        if i > 0 && !delay.is_zero() {
+            std::thread::sleep(delay);
+        }
+        input.type_char(ch)?;
+        input.flush();
+    }
+    Ok(())
+}
+
+/// Press a key chord such as `ctrl+shift+t`
+///
+/// Keys are pressed in order and released in reverse order. Key names are
+/// case-insensitive aliases (`ctrl`, `alt`, `shift`, `super`, `enter`, `esc`, `f5`, ...),
+/// single characters, or platform key names such as X11 keysym names.
+pub fn press_keys(chord: &str) -> Result<(), String> {
+    let keys = parse_chord(chord)?;
+    let input = InputDevice::open()?;
+    input.press_chord(&keys)?;
+    input.flush();
+    Ok(())
+}
+
+/// Split a chord into key names, translating common aliases
+fn parse_chord(chord: &str) -> Result<Vec<String>, String> {
+    let keys: Vec<String> = chord
+        .split('+')
+        .map(str::trim)
+        .map(|key| {
+            if key.is_empty() {
+                return Err(format!("Invalid key chord: '{}'", chord));
+            }
+            let alias = match key.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => "Control_L",
+                "shift" => "Shift_L",
+                "alt" => "Alt_L",
+                "super" | "win" | "meta" | "cmd" => "Super_L",
+                "enter" | "return" => "Return",
+                "esc" | "escape" => "Escape",
+                "tab" => "Tab",
+                "space" => "space",
+                "backspace" => "BackSpace",
+                "delete" | "del" => "Delete",
+                "insert" | "ins" => "Insert",
+                "home" => "Home",
+                "end" => "End",
+                "pageup" | "pgup" => "Prior",
+                "pagedown" | "pgdn" => "Next",
+                "up" => "Up",
+                "down" => "Down",
+                "left" => "Left",
+                "right" => "Right",
+                "plus" => "plus",
+                "minus" => "minus",
+                _ => {
+                    // Function keys are written F1..F24 in keysym names
+                    let lower = key.to_ascii_lowercase();
+                    return Ok(match lower.strip_prefix('f') {
+                        Some(n) if !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()) => {
+                            format!("F{}", n)
+                        }
+                        _ => key.to_string(),
+                    });
+                }
+            };
+            Ok(alias.to_string())
+        })
+        .collect::<Result<_, _>>()?;
+
+    if keys.is_empty() {
+        return Err("No keys given".to_string());
+    }
+    Ok(keys)
+}
+
+/// Synthetic input device (platform-specific implementation)
+///
+/// # Platform-specific behavior
+/// - Linux: Uses the XTEST extension
+#[cfg(target_os = "linux")]
+struct InputDevice {
+    display: XDisplay,
+    /// Unused keycode temporarily bound to keysyms missing from the keyboard layout
+    scratch_keycode: Cell<Option<xlib::KeyCode>>,
+    /// Keysym the scratch keycode is currently bound to
+    scratch_keysym: Cell<Option<xlib::KeySym>>,
+}
+
+#[cfg(target_os = "linux")]
+const XK_SHIFT_L: xlib::KeySym = 0xffe1;
+#[cfg(target_os = "linux")]
+const XK_TAB: xlib::KeySym = 0xff09;
+#[cfg(target_os = "linux")]
+const XK_RETURN: xlib::KeySym = 0xff0d;
+
+#[cfg(target_os = "linux")]
+impl InputDevice {
+    fn open() -> Result<Self, String> {
+        let display = XDisplay::open()?;
+        let (mut event_base, mut error_base, mut major, mut minor) = (0, 0, 0, 0);
+        let supported = unsafe {
+            xtest::XTestQueryExtension(
+                display.raw(),
+                &mut event_base,
+                &mut error_base,
+                &mut major,
+                &mut minor,
+            )
+        };
+        if supported == 0 {
+            return Err("The X server does not support the XTEST extension".to_string());
+        }
+        Ok(Self {
+            display,
+            scratch_keycode: Cell::new(None),
+            scratch_keysym: Cell::new(None),
+        })
+    }
+
+    fn motion(&self, x: i32, y: i32) {
+        unsafe {
+            // Screen -1 moves the pointer relative to the root window of the current screen
+            xtest::XTestFakeMotionEvent(self.display.raw(), -1, x, y, xlib::CurrentTime);
+        }
+    }
+
+    fn button(&self, button: u32, pressed: bool) {
+        unsafe {
+            xtest::XTestFakeButtonEvent(
+                self.display.raw(),
+                button,
+                pressed as i32,
+                xlib::CurrentTime,
+            );
+        }
+    }
+
+    fn key(&self, keycode: xlib::KeyCode, pressed: bool) {
+        unsafe {
+            xtest::XTestFakeKeyEvent(
+                self.display.raw(),
+                keycode as u32,
+                pressed as i32,
+                xlib::CurrentTime,
+            );
+        }
+    }
+
+    fn flush(&self) {
+        self.display.flush();
+    }
+
+    fn type_char(&self, ch: char) -> Result<(), String> {
+        let (keycode, shift) = self.keycode_for(char_keysym(ch))?;
+        if shift {
+            self.key(self.keycode_for(XK_SHIFT_L)?.0, true);
+        }
+        self.key(keycode, true);
+        self.key(keycode, false);
+        if shift {
+            self.key(self.keycode_for(XK_SHIFT_L)?.0, false);
+        }
+        Ok(())
+    }
+
+    /// Press and release a chord, adding Shift for keys that need it
+    ///
+    /// Only one key of a chord can be bound to the scratch keycode, since rebinding it
+    /// would change a key that is still held down.
+    fn press_chord(&self, keys: &[String]) -> Result<(), String> {
+        let keysyms = keys
+            .iter()
+            .map(|name| {
+                let mut chars = name.chars();
+                let keysym = if let (Some(c), None) = (chars.next(), chars.next()) {
+                    char_keysym(c)
+                } else {
+                    let c_name = CString::new(name.as_str()).unwrap_or_default();
+                    unsafe { xlib::XStringToKeysym(c_name.as_ptr()) }
+                };
+                if keysym == 0 {
+                    return Err(format!("Unknown key: '{}'", name));
+                }
+                Ok(keysym)
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let missing = keysyms
+            .iter()
+            .filter(|keysym| self.layout_keycode(**keysym).is_none())
+            .count();
+        if missing > 1 {
+            return Err(format!(
+                "Chord '{}' has {} keys missing from the keyboard layout; at most one is supported",
+                keys.join("+"),
+                missing
+            ));
+        }
+
+        let mut keycodes = Vec::new();
+        let mut needs_shift = false;
+        for keysym in keysyms {
+            let (keycode, shift) = self.keycode_for(keysym)?;
+            needs_shift |= shift;
+            keycodes.push(keycode);
+        }
+        if needs_shift {
+            let shift = self.keycode_for(XK_SHIFT_L)?.0;
+            if !keycodes.contains(&shift) {
+                keycodes.insert(0, shift);
+            }
+        }
+
+        for keycode in &keycodes {
+            self.key(*keycode, true);
+        }
+        for keycode in keycodes.iter().rev() {
+            self.key(*keycode, false);
+        }
+        Ok(())
+    }
+
+    /// Find the keycode producing a keysym and whether Shift is needed for it
+    ///
+    /// Keysyms missing from the layout are bound to the scratch keycode.
+    fn keycode_for(&self, keysym: xlib::KeySym) -> Result<(xlib::KeyCode, bool), String> {
+        match self.layout_keycode(keysym) {
+            Some(found) => Ok(found),
+            None => Ok((self.bind_scratch(keysym)?, false)),
+        }
+    }
+
+    /// Find the keycode producing a keysym in the current layout and whether Shift is needed
+    ///
+    /// Keysyms only reachable at level 3 or above (AltGr) are treated as missing from the
+    /// layout, since pressing their keycode alone would type a different character. The
+    /// scratch keycode counts as part of the layout while it is bound.
+    fn layout_keycode(&self, keysym: xlib::KeySym) -> Option<(xlib::KeyCode, bool)> {
+        let raw = self.display.raw();
+        let keycode = unsafe { xlib::XKeysymToKeycode(raw, keysym) };
+        if keycode == 0 {
+            return None;
+        }
+        let (unshifted, shifted) = unsafe {
+            (
+                xlib::XkbKeycodeToKeysym(raw, keycode, 0, 0),
+                xlib::XkbKeycodeToKeysym(raw, keycode, 0, 1),
+            )
+        };
+        if unshifted == keysym {
+            Some((keycode, false))
+        } else if shifted == keysym {
+            Some((keycode, true))
+        } else {
+            None
+        }
+    }
+
+    /// Bind a keysym to the scratch keycode, waiting for earlier events on it first
+    fn bind_scratch(&self, keysym: xlib::KeySym) -> Result<xlib::KeyCode, String> {
+        let scratch = match self.scratch_keycode.get() {
+            Some(keycode) => keycode,
+            None => {
+                let keycode = self.find_unused_keycode()?;
+                self.scratch_keycode.set(Some(keycode));
+                keycode
+            }
+        };
+        match self.scratch_keysym.get() {
+            Some(bound) if bound == keysym => return Ok(scratch),
+            Some(_) => {
+                // Clients translate key events with the mapping current when they read
+                // them, so let them see the previous character before rebinding
+                self.display.sync();
+                std::thread::sleep(SCRATCH_UNBIND_DELAY);
+            }
+            None => {}
+        }
+        self.bind_keycode(scratch, keysym)?;
+        self.scratch_keysym.set(Some(keysym));
+        Ok(scratch)
+    }
+
+    fn bind_keycode(&self, keycode: xlib::KeyCode, keysym: xlib::KeySym) -> Result<(), String> {
+        let mut keysyms = [keysym, keysym];
+        // The trap syncs, so the new mapping is in place before the next key event is faked
+        let (_, error) = self.display.trap_errors(|| unsafe {
+            xlib::XChangeKeyboardMapping(
+                self.display.raw(),
+                keycode as i32,
+                keysyms.len() as i32,
+                keysyms.as_mut_ptr(),
+                1,
+            );
+        });
+        if let Some(code) = error {
+            return Err(format!(
+                "Failed to bind keycode {} (X error {})",
+                keycode, code
+            ));
+        }
+        Ok(())
+    }
+
+    fn find_unused_keycode(&self) -> Result<xlib::KeyCode, String> {
+        let raw = self.display.raw();
+        let (mut min, mut max, mut per_keycode) = (0, 0, 0);
+        unsafe {
+            xlib::XDisplayKeycodes(raw, &mut min, &mut max);
+            let count = max - min + 1;
+            let mapping = xlib::XGetKeyboardMapping(raw, min as u8, count, &mut per_keycode);
+            if mapping.is_null() {
+                return Err("Failed to read keyboard mapping".to_string());
+            }
+            let per_keycode = per_keycode as usize;
+            let keysyms = std::slice::from_raw_parts(mapping, count as usize * per_keycode);
+            let unused = keysyms
+                .chunks(per_keycode)
+                .enumerate()
+                .rev()
+                .find(|(_, syms)| syms.iter().all(|sym| *sym == 0))
+                .map(|(offset, _)| (min as usize + offset) as xlib::KeyCode);
+            xlib::XFree(mapping as *mut _);
+            unused.ok_or_else(|| "No free keycode available to type this character".to_string())
+        }
+    }
+}
+
+/// Keysym for a character
+///
+/// Latin-1 keysyms equal their code point; everything else uses the Unicode range.
+#[cfg(target_os = "linux")]
+fn char_keysym(ch: char) -> xlib::KeySym {
+    match ch {
+        '\n' => XK_RETURN,
+        '\t' => XK_TAB,
+        ' '..='~' | '\u{a0}'..='\u{ff}' => ch as xlib::KeySym,
+        _ => 0x0100_0000 | ch as xlib::KeySym,
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for InputDevice {
+    fn drop(&mut self) {
+        if let Some(keycode) = self.scratch_keycode.get() {
+            // Give clients time to translate the last key event before the binding goes away
+            self.display.sync();
+            std::thread::sleep(SCRATCH_UNBIND_DELAY);
+            let _ = self.bind_keycode(keycode, 0);
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+struct InputDevice;
+
+#[cfg(not(target_os = "linux"))]
+impl InputDevice {
+    fn open() -> Result<Self, String> {
+        Err("Input synthesis is not supported on this platform".to_string())
+    }
+
+    fn motion(&self, _x: i32, _y: i32) {}
+
+    fn button(&self, _button: u32, _pressed: bool) {}
+
+    fn flush(&self) {}
+
+    fn type_char(&self, _ch: char) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn press_chord(&self, _keys: &[String]) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_chord_translates_aliases() {
+        assert_eq!(
+            parse_chord("ctrl+shift+t").unwrap(),
+            vec!["Control_L", "Shift_L", "t"]
+        );
+        assert_eq!(parse_chord(" Alt + F4 ").unwrap(), vec!["Alt_L", "F4"]);
+        assert_eq!(parse_chord("win+pgdn").unwrap(), vec!["Super_L", "Next"]);
+        assert_eq!(parse_chord("Enter").unwrap(), vec!["Return"]);
+    }
+
+    #[test]
+    fn parse_chord_keeps_unknown_keysym_names() {
+        assert_eq!(parse_chord("XF86AudioMute").unwrap(), vec!["XF86AudioMute"]);
+        // Only f followed by digits is a function key
+        assert_eq!(parse_chord("ctrl+f").unwrap(), vec!["Control_L", "f"]);
+    }
+
+    #[test]
+    fn parse_chord_rejects_empty_keys() {
+        assert!(parse_chord("").is_err());
+        assert!(parse_chord("ctrl+").is_err());
+        assert!(parse_chord("ctrl++s").is_err());
+    }
+
+    #[test]
+    fn out_of_range_gestures_are_rejected_before_any_input() {
+        let too_long = Duration::from_millis(MAX_DRAG_DURATION_MS + 1);
+        assert!(mouse_drag((0, 0), (10, 10), 1, too_long)
+            .unwrap_err()
+            .contains("at most"));
+        let notches = MAX_SCROLL_NOTCHES as i32 + 1;
+        assert!(mouse_scroll(None, 0, -notches)
+            .unwrap_err()
+            .contains("between"));
+        assert!(mouse_click(None, 1, MAX_CLICKS + 1)
+            .unwrap_err()
+            .contains("between"));
+    }
+}
@@ -1 +1,19 @@
+pub mod annotate;
+pub mod capture;
+pub mod capture_store;
+pub mod change_wait;
+pub mod coordinates;
+pub mod cursor;
+pub mod drawing;
+pub mod geometry;
+pub mod image_diff;
+#[cfg(feature = "input")]
+pub mod input_ops;
+pub mod monitor_ops;
+pub mod output;
+pub mod process_ops;
+pub mod recording;
+pub mod thumbnails;
 pub mod window_ops;
+#[cfg(target_os = "linux")]
+pub mod x11_display;
@@ -0,0 +1,145 @@
+use serde::Serialize;
+use xcap::Monitor;
+
+use super::geometry::Rect;
+
+#[cfg(target_os = "linux")]
+use super::x11_display::XDisplay;
+#[cfg(target_os = "linux")]
+use x11::{xlib, xrandr};
+
+/// Millimetres per inch, for DPI computation
+const MM_PER_INCH: f32 = 25.4;
+
+/// Monitor record returned by `get_monitor_count`
+#[derive(Debug, Clone, Serialize)]
+pub struct MonitorInfo {
+    pub index: usize,
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub is_primary: bool,
+    pub is_builtin: bool,
+    /// Ratio of physical pixels to logical points
+    pub scale_factor: f32,
+    /// Rotation in degrees
+    pub rotation: f32,
+    /// Refresh rate in Hz
+    pub frequency: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width_mm: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height_mm: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dpi_x: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dpi_y: Option<f32>,
+    /// The part of the monitor not covered by panels and docks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub work_area: Option<Rect>,
+}
+
+impl MonitorInfo {
+    pub fn from_monitor(index: usize, monitor: &Monitor) -> Self {
+        Self {
+            index,
+            name: monitor.name().unwrap_or_default(),
+            x: monitor.x().unwrap_or(0),
+            y: monitor.y().unwrap_or(0),
+            width: monitor.width().unwrap_or(0),
+            height: monitor.height().unwrap_or(0),
+            is_primary: monitor.is_primary().unwrap_or(false),
+            is_builtin: monitor.is_builtin().unwrap_or(false),
+            scale_factor: monitor.scale_factor().unwrap_or(1.0),
+            rotation: monitor.rotation().unwrap_or(0.0),
+            frequency: monitor.frequency().unwrap_or(0.0),
+            width_mm: None,
+            height_mm: None,
+            dpi_x: None,
+            dpi_y: None,
+            work_area: None,
+        }
+    }
+
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.x, self.y, self.width, self.height)
+    }
+
+    fn set_physical_size(&mut self, width_mm: u32, height_mm: u32) {
+        // Projectors and some virtual outputs report 0 mm
+        if width_mm == 0 || height_mm == 0 {
+            return;
+        }
+        self.width_mm = Some(width_mm);
+        self.height_mm = Some(height_mm);
+        self.dpi_x = Some(self.width as f32 * MM_PER_INCH / width_mm as f32);
+        self.dpi_y = Some(self.height as f32 * MM_PER_INCH / height_mm as f32);
+    }
+}
+
+/// List all monitors with their geometry, scaling and work area
+///
+/// # Platform-specific behavior
+/// - Linux: Physical size comes from XRandR monitors matched by geometry; the work area
+///   is `_NET_WORKAREA` of the current workspace clipped to each monitor
+/// - Other platforms: Physical size, DPI and work area are not reported
+pub fn list_monitors() -> Result<Vec<MonitorInfo>, String> {
+    let monitors = Monitor::all().map_err(|e| format!("Failed to get monitors: {}", e))?;
+    let mut infos: Vec<MonitorInfo> = monitors
+        .iter()
+        .enumerate()
+        .map(|(index, monitor)| MonitorInfo::from_monitor(index, monitor))
+        .collect();
+    apply_x11_details(&mut infos);
+    Ok(infos)
+}
+
+#[cfg(target_os = "linux")]
+fn apply_x11_details(infos: &mut [MonitorInfo]) {
+    let Ok(display) = XDisplay::open() else {
+        return;
+    };
+    let root = display.root();
+
+    let mut count = 0;
+    let randr_monitors =
+        unsafe { xrandr::XRRGetMonitors(display.raw(), root, xlib::True, &mut count) };
+    if !randr_monitors.is_null() {
+        let randr_monitors = unsafe { std::slice::from_raw_parts(randr_monitors, count as usize) };
+        for info in infos.iter_mut() {
+            let matching = randr_monitors.iter().find(|m| {
+                m.x == info.x
+                    && m.y == info.y
+                    && m.width as u32 == info.width
+                    && m.height as u32 == info.height
+            });
+            if let Some(m) = matching {
+                info.set_physical_size(m.mwidth.max(0) as u32, m.mheight.max(0) as u32);
+            }
+        }
+        unsafe {
+            xrandr::XRRFreeMonitors(randr_monitors.as_ptr() as *mut _);
+        }
+    }
+
+    // _NET_WORKAREA holds one x, y, width, height quadruple per workspace
+    let current = display.long(root, "_NET_CURRENT_DESKTOP").unwrap_or(0) as usize;
+    if let Some(work_areas) = display.longs(root, "_NET_WORKAREA") {
+        if let Some(area) = work_areas.chunks_exact(4).nth(current) {
+            let work_area = Rect::new(
+                area[0] as i32,
+                area[1] as i32,
+                area[2] as u32,
+                area[3] as u32,
+            );
+            for info in infos.iter_mut() {
+                info.work_area = info.rect().intersection(&work_area);
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn apply_x11_details(_infos: &mut [MonitorInfo]) {}
@@ -0,0 +1,436 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::capture::{encode_image, Capture, CaptureSource, ImageEncoding};
+
+/// File name template used when `save_to` names a directory
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "{date}/{app}-{window_id}-{ts}.png";
+
+/// Longest value substituted for a template placeholder, in characters
+const MAX_LABEL_CHARS: usize = 64;
+
+/// How often a numeric suffix is tried when the target file already exists
+const MAX_NAME_ATTEMPTS: u32 = 100;
+
+/// Where tool results may be written to disk
+#[derive(Clone, Debug)]
+pub struct OutputConfig {
+    /// Directory relative `save_to` paths are resolved against; also a write root
+    pub dir: Option<PathBuf>,
+    /// File name template, see `expand_template`
+    pub filename_template: String,
+    /// Further directories files may be written to
+    pub write_roots: Vec<PathBuf>,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            filename_template: DEFAULT_FILENAME_TEMPLATE.to_string(),
+            write_roots: Vec::new(),
+        }
+    }
+}
+
+impl OutputConfig {
+    /// Directories writes are restricted to, the output directory first
+    fn roots(&self) -> impl Iterator<Item = &PathBuf> {
+        self.dir.iter().chain(self.write_roots.iter())
+    }
+}
+
+/// Values substituted into file name templates
+pub struct FileLabels {
+    /// Application name, or "screen" / "region" / "thumbnails" for other captures
+    pub app: String,
+    pub title: String,
+    /// Window ID, or the monitor index for monitor captures
+    pub window_id: String,
+    pub capture_id: Option<u64>,
+    pub captured_at: SystemTime,
+}
+
+impl FileLabels {
+    pub fn for_capture(capture: &Capture, capture_id: Option<u64>) -> Self {
+        let (app, window_id) = match capture.source {
+            CaptureSource::Monitor { index } => {
+                ("screen".to_string(), index.unwrap_or(0).to_string())
+            }
+            CaptureSource::Window { window_id } => (
+                capture.app_name.clone().unwrap_or_default(),
+                window_id.to_string(),
+            ),
+            CaptureSource::Region { .. } => ("region".to_string(), "0".to_string()),
+            CaptureSource::ContactSheet => ("thumbnails".to_string(), "0".to_string()),
+        };
+        Self {
+            app,
+            title: capture.title.clone().unwrap_or_default(),
+            window_id,
+            capture_id,
+            captured_at: capture.captured_at,
+        }
+    }
+}
+
+/// Replace characters that are unsafe in file names with underscores
+///
+/// Path separators, dots at either end and control characters never survive, so a
+/// sanitised label cannot leave the directory the template puts it in.
+pub fn sanitize_label(label: &str) -> String {
+    let mut sanitized = String::new();
+    for c in label.chars() {
+        if c.is_alphanumeric() || matches!(c, '-' | '.') {
+            sanitized.push(c);
+        } else if !sanitized.ends_with('_') {
+            sanitized.push('_');
+        }
+    }
+    let trimmed: String = sanitized
+        .trim_matches(|c| c == '_' || c == '.')
+        .chars()
+        .take(MAX_LABEL_CHARS)
+        .collect();
+    if trimmed.is_empty() {
+        "untitled".to_string()
+    } else {
+        trimmed
+    }
+}
+
+/// Substitute placeholders in a path template
+///
+/// Supported placeholders: `{date}` (YYYY-MM-DD, UTC), `{time}` (HHMMSS, UTC), `{ts}`
+/// (Unix time in milliseconds), `{app}`, `{title}`, `{window_id}` and `{id}` (capture ID).
+/// Every substituted value is sanitised; `/` in the template itself creates directories.
+pub fn expand_template(template: &str, labels: &FileLabels) -> String {
+    let elapsed = labels
+        .captured_at
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let seconds = elapsed.as_secs();
+    let (year, month, day) = civil_date(seconds / 86_400);
+    let of_day = seconds % 86_400;
+
+    template
+        .replace("{date}", &format!("{:04}-{:02}-{:02}", year, month, day))
+        .replace(
+            "{time}",
+            &format!(
+                "{:02}{:02}{:02}",
+                of_day / 3600,
+                of_day % 3600 / 60,
+                of_day % 60
+            ),
+        )
+        .replace("{ts}", &elapsed.as_millis().to_string())
+        .replace("{app}", &sanitize_label(&labels.app))
+        .replace("{title}", &sanitize_label(&labels.title))
+        .replace("{window_id}", &sanitize_label(&labels.window_id))
+        .replace(
+            "{id}",
+            &labels
+                .capture_id
+                .map(|id| id.to_string())
+                .unwrap_or_else(|| "0".to_string()),
+        )
+}
+
+/// Convert days since the Unix epoch to a (year, month, day) date in the Gregorian calendar
+fn civil_date(days: u64) -> (i64, u32, u32) {
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Save a capture to disk, as JPEG if the file name ends in `.jpg` or `.jpeg`, else as PNG
+///
+/// `.png` is appended to file names without either extension. Recordings are saved as
+/// their animation with the extension of its format.
+///
+/// # Arguments
+/// * `config` - Output directory, file name template and write roots
+/// * `save_to` - Target file or directory; see `resolve_path`
+/// * `capture` - The capture to save
+/// * `capture_id` - ID of the capture in the capture store, for the `{id}` placeholder
+///
+/// # Returns
+/// * `Ok(PathBuf)` - The absolute path the file was written to
+/// * `Err(String)` - Error message if saving is disabled, the path is outside the write
+///   roots or writing failed
+pub fn save_capture(
+    config: &OutputConfig,
+    save_to: &str,
+    capture: &Capture,
+    capture_id: u64,
+) -> Result<PathBuf, String> {
+    let labels = FileLabels::for_capture(capture, Some(capture_id));
+    if let Some(animation) = &capture.animation {
+        return save_bytes(
+            config,
+            save_to,
+            &labels,
+            animation.format.extension(),
+            &animation.data,
+        );
+    }
+    let path = resolve_path(config, save_to, &labels)?;
+
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase());
+    let (path, encoding) = match extension.as_deref() {
+        Some("png") => (path, ImageEncoding::Png),
+        Some("jpg") | Some("jpeg") => (path, ImageEncoding::Jpeg),
+        // Anything else (e.g. a title such as "v1.2") is part of the name
+        _ => (ensure_extension(path, "png"), ImageEncoding::Png),
+    };
+
+    let data = encode_image(&capture.image, encoding)?;
+    write_new_file(&path, &data)
+}
+
+/// Save encoded data to disk, appending `.{extension}` unless the name already ends in it
+///
+/// See `save_capture` for the arguments and return value.
+pub fn save_bytes(
+    config: &OutputConfig,
+    save_to: &str,
+    labels: &FileLabels,
+    extension: &str,
+    data: &[u8],
+) -> Result<PathBuf, String> {
+    let path = ensure_extension(resolve_path(config, save_to, labels)?, extension);
+    write_new_file(&path, data)
+}
+
+/// Append `.{extension}` to a file name unless it already has that extension
+///
+/// Dots elsewhere in the name, as in `build-1.2`, are kept as part of the name.
+fn ensure_extension(path: PathBuf, extension: &str) -> PathBuf {
+    let has_extension = path
+        .extension()
+        .is_some_and(|ext| ext.to_string_lossy().eq_ignore_ascii_case(extension));
+    if has_extension {
+        return path;
+    }
+    let mut name = path.into_os_string();
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
+
+/// Resolve `save_to` to a file path inside one of the write roots
+///
+/// Placeholders in `save_to` are expanded. Relative paths are resolved against the output
+/// directory. If `save_to` ends with a path separator, is `.` or names an existing
+/// directory, the file name template is appended.
+fn resolve_path(
+    config: &OutputConfig,
+    save_to: &str,
+    labels: &FileLabels,
+) -> Result<PathBuf, String> {
+    if config.roots().next().is_none() {
+        return Err(
+            "Saving files is disabled; start the server with --output-dir or --write-root"
+                .to_string(),
+        );
+    }
+
+    let expanded = expand_template(save_to, labels);
+    let mut path = PathBuf::from(&expanded);
+    if path.is_relative() {
+        let dir = config.dir.as_ref().ok_or_else(|| {
+            "Relative save_to paths need an output directory; start the server with --output-dir"
+                .to_string()
+        })?;
+        path = dir.join(path);
+    }
+    if expanded.is_empty()
+        || expanded == "."
+        || expanded.ends_with('/')
+        || expanded.ends_with(std::path::MAIN_SEPARATOR)
+        || path.is_dir()
+    {
+        path = path.join(expand_template(&config.filename_template, labels));
+    }
+    let path = std::path::absolute(&path)
+        .map_err(|e| format!("Failed to resolve {}: {}", path.display(), e))?;
+
+    if path.components().any(|c| c == Component::ParentDir) {
+        return Err(format!("Path {} must not contain '..'", path.display()));
+    }
+    if path.file_name().is_none() {
+        return Err(format!("Path {} does not name a file", path.display()));
+    }
+    check_inside_roots(config, &path)?;
+    Ok(path)
+}
+
+/// Create the parent directories of `path` and make sure they lie inside a write root
+///
+/// The check runs on canonical paths after the directories exist, so symbolic links
+/// cannot be used to escape the roots.
+fn check_inside_roots(config: &OutputConfig, path: &Path) -> Result<(), String> {
+    let parent = path
+        .parent()
+        .ok_or_else(|| format!("Path {} has no parent directory", path.display()))?;
+
+    // Lexical check first, so that no directories are created outside the roots
+    let lexically_inside = config.roots().any(|root| {
+        let root = std::path::absolute(root).unwrap_or_else(|_| root.clone());
+        parent.starts_with(&root)
+    });
+    if !lexically_inside {
+        return Err(format!(
+            "Path {} is outside the allowed write roots",
+            path.display()
+        ));
+    }
+
+    std::fs::create_dir_all(parent)
+        .map_err(|e| format!("Failed to create directory {}: {}", parent.display(), e))?;
+    let parent = parent
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve {}: {}", parent.display(), e))?;
+
+    let inside = config.roots().any(|root| {
+        std::fs::create_dir_all(root).is_ok()
+            && root
+                .canonicalize()
+                .map(|root| parent.starts_with(root))
+                .unwrap_or(false)
+    });
+    if inside {
+        Ok(())
+    } else {
+        Err(format!(
+            "Path {} is outside the allowed write roots",
+            path.display()
+        ))
+    }
+}
+
+/// Write `data` to a file that does not exist yet, adding a numeric suffix on collisions
+///
+/// Existing files and symbolic links are never overwritten or followed.
+fn write_new_file(path: &Path, data: &[u8]) -> Result<PathBuf, String> {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+
+    for attempt in 0..MAX_NAME_ATTEMPTS {
+        let candidate = if attempt == 0 {
+            path.to_path_buf()
+        } else {
+            path.with_file_name(format!("{}-{}{}", stem, attempt, extension))
+        };
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&candidate)
+        {
+            Ok(mut file) => {
+                file.write_all(data)
+                    .map_err(|e| format!("Failed to write {}: {}", candidate.display(), e))?;
+                return Ok(candidate.canonicalize().unwrap_or(candidate));
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => {
+                return Err(format!("Failed to create {}: {}", candidate.display(), e));
+            }
+        }
+    }
+
+    Err(format!(
+        "Could not find a free file name for {}",
+        path.display()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn labels(app: &str, title: &str) -> FileLabels {
+        FileLabels {
+            app: app.to_string(),
+            title: title.to_string(),
+            window_id: "12345".to_string(),
+            capture_id: Some(7),
+            // 2024-02-29 13:45:06.789 UTC
+            captured_at: UNIX_EPOCH + Duration::from_millis(1_709_214_306_789),
+        }
+    }
+
+    #[test]
+    fn sanitize_label_never_leaves_the_directory() {
+        assert_eq!(sanitize_label("../../etc/passwd"), "etc_passwd");
+        assert_eq!(sanitize_label("Untitled - Notepad"), "Untitled_-_Notepad");
+        assert_eq!(sanitize_label(".hidden."), "hidden");
+        assert_eq!(sanitize_label("a\u{0}b\nc"), "a_b_c");
+        assert_eq!(sanitize_label("日本語"), "日本語");
+        assert_eq!(sanitize_label("///"), "untitled");
+        assert_eq!(sanitize_label(&"x".repeat(100)).len(), MAX_LABEL_CHARS);
+    }
+
+    #[test]
+    fn expand_template_substitutes_every_placeholder() {
+        let expanded = expand_template(
+            "{app}/{date}_{time}_{title}_{window_id}_{id}_{ts}",
+            &labels("firefox", "Mozilla Firefox"),
+        );
+        assert_eq!(
+            expanded,
+            "firefox/2024-02-29_134506_Mozilla_Firefox_12345_7_1709214306789"
+        );
+    }
+
+    #[test]
+    fn expand_template_sanitises_values_but_not_the_template() {
+        let expanded = expand_template("shots/{app}-{title}", &labels("../x", "a/b"));
+        assert_eq!(expanded, "shots/x-a_b");
+    }
+
+    #[test]
+    fn civil_date_handles_epoch_leap_days_and_century_rules() {
+        assert_eq!(civil_date(0), (1970, 1, 1));
+        assert_eq!(civil_date(59), (1970, 3, 1));
+        assert_eq!(civil_date(11_016), (2000, 2, 29));
+        assert_eq!(civil_date(19_782), (2024, 2, 29));
+        assert_eq!(civil_date(47_541), (2100, 3, 1));
+    }
+
+    #[test]
+    fn ensure_extension_appends_unless_present() {
+        assert_eq!(
+            ensure_extension(PathBuf::from("out/build-1.2"), "gif"),
+            PathBuf::from("out/build-1.2.gif")
+        );
+        assert_eq!(
+            ensure_extension(PathBuf::from("out/clip.GIF"), "gif"),
+            PathBuf::from("out/clip.GIF")
+        );
+        assert_eq!(
+            ensure_extension(PathBuf::from("out/clip.png"), "gif"),
+            PathBuf::from("out/clip.png.gif")
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use serde::Serialize;
+use std::time::Duration;
+
+#[cfg(unix)]
+use std::time::Instant;
+
+/// Interval between liveness checks while waiting for a process to exit
+#[cfg(unix)]
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Time allowed for a process to disappear after SIGKILL
+#[cfg(unix)]
+const KILL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Result of terminating a process
+#[derive(Debug, Clone, Serialize)]
+pub struct TerminateReport {
+    pub pid: u32,
+    /// The last signal that was sent
+    pub signal: &'static str,
+    /// Whether the process is gone
+    pub exited: bool,
+    pub elapsed_ms: u64,
+}
+
+/// Details about the process that owns a window
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_pid: Option<u32>,
+    /// Path of the executable image
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exe: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cmdline: Option<Vec<String>>,
+    /// Resident set size in bytes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rss_bytes: Option<u64>,
+}
+
+/// Collect details about a process
+///
+/// Fields that cannot be read, e.g. because the process belongs to another user,
+/// are left empty.
+///
+/// # Platform-specific behavior
+/// - Linux: Reads `/proc/<pid>/exe`, `/proc/<pid>/cmdline` and `/proc/<pid>/status`
+/// - Other platforms: Only the PID is reported
+#[cfg(target_os = "linux")]
+pub fn process_info(pid: u32) -> ProcessInfo {
+    let proc_dir = std::path::PathBuf::from(format!("/proc/{}", pid));
+
+    let exe = std::fs::read_link(proc_dir.join("exe"))
+        .ok()
+        .map(|path| path.to_string_lossy().into_owned());
+
+    let cmdline = std::fs::read(proc_dir.join("cmdline"))
+        .ok()
+        .filter(|bytes| !bytes.is_empty())
+        .map(|bytes| {
+            bytes
+                .split(|b| *b == 0)
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| String::from_utf8_lossy(arg).into_owned())
+                .collect()
+        });
+
+    let status = std::fs::read_to_string(proc_dir.join("status")).unwrap_or_default();
+    let status_field = |name: &str| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+            .and_then(|value| value.split_whitespace().next())
+            .and_then(|value| value.parse::<u64>().ok())
+    };
+
+    ProcessInfo {
+        pid,
+        parent_pid: status_field("PPid").map(|ppid| ppid as u32),
+        exe,
+        cmdline,
+        // VmRSS is reported in kB
+        rss_bytes: status_field("VmRSS").map(|kb| kb * 1024),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn process_info(pid: u32) -> ProcessInfo {
+    ProcessInfo {
+        pid,
+        parent_pid: None,
+        exe: None,
+        cmdline: None,
+        rss_bytes: None,
+    }
+}
+
+/// Start time of a process, used to tell it apart from a later process reusing its PID
+///
+/// # Returns
+/// * `Some(u64)` - Start time in clock ticks since boot
+/// * `None` - The process does not exist or its start time is unavailable
+///
+/// # Platform-specific behavior
+/// - Linux: Reads field 22 of `/proc/<pid>/stat`
+/// - Other platforms: Always `None`
+#[cfg(target_os = "linux")]
+pub fn process_start_time(pid: u32) -> Option<u64> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // Fields after the parenthesised command name start at field 3 (state)
+    let (_, rest) = stat.rsplit_once(')')?;
+    rest.split_whitespace().nth(19)?.parse().ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn process_start_time(_pid: u32) -> Option<u64> {
+    None
+}
+
+/// Terminate a process, escalating from SIGTERM to SIGKILL
+///
+/// SIGTERM is sent first. If the process is still alive after `grace_period`,
+/// SIGKILL is sent and the process is given a short time to disappear.
+///
+/// # Arguments
+/// * `pid` - The process to terminate
+/// * `grace_period` - How long to wait after SIGTERM before sending SIGKILL
+///
+/// # Returns
+/// * `Ok(TerminateReport)` - Which signal was needed and whether the process exited
+/// * `Err(String)` - Error message if the process does not exist or cannot be signalled
+#[cfg(unix)]
+pub fn terminate_process(pid: u32, grace_period: Duration) -> Result<TerminateReport, String> {
+    if pid <= 1 || pid == std::process::id() {
+        return Err(format!("Refusing to terminate process {}", pid));
+    }
+
+    let start = Instant::now();
+    send_signal(pid, libc::SIGTERM)?;
+    if wait_for_exit(pid, grace_period) {
+        return Ok(TerminateReport {
+            pid,
+            signal: "SIGTERM",
+            exited: true,
+            elapsed_ms: start.elapsed().as_millis() as u64,
+        });
+    }
+
+    send_signal(pid, libc::SIGKILL)?;
+    let exited = wait_for_exit(pid, KILL_TIMEOUT);
+    Ok(TerminateReport {
+        pid,
+        signal: "SIGKILL",
+        exited,
+        elapsed_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+#[cfg(not(unix))]
+pub fn terminate_process(_pid: u32, _grace_period: Duration) -> Result<TerminateReport, String> {
+    Err("Process termination is not supported on this platform".to_string())
+}
+
+#[cfg(unix)]
+fn send_signal(pid: u32, signal: libc::c_int) -> Result<(), String> {
+    let result = unsafe { libc::kill(pid as libc::pid_t, signal) };
+    if result != 0 {
+        let error = std::io::Error::last_os_error();
+        return Err(match error.raw_os_error() {
+            Some(libc::ESRCH) => format!("Process {} does not exist", pid),
+            _ => format!("Failed to signal process {}: {}", pid, error),
+        });
+    }
+    Ok(())
+}
+
+/// Whether a process is still running
+///
+/// A zombie has exited and only waits for its parent to reap it, so it counts as gone.
+#[cfg(unix)]
+fn is_alive(pid: u32) -> bool {
+    let result = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    // EPERM means the process exists but belongs to another user
+    let exists = result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM);
+    exists && !is_zombie(pid)
+}
+
+/// Whether `/proc/<pid>/stat` reports the zombie state `Z`
+#[cfg(target_os = "linux")]
+fn is_zombie(pid: u32) -> bool {
+    std::fs::read_to_string(format!("/proc/{}/stat", pid))
+        .ok()
+        .and_then(|stat| {
+            // The command name is in parentheses and may contain spaces or parentheses
+            let (_, rest) = stat.rsplit_once(')')?;
+            rest.split_whitespace().next().map(|state| state == "Z")
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn is_zombie(_pid: u32) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn wait_for_exit(pid: u32, timeout: Duration) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        if !is_alive(pid) {
+            return true;
+        }
+        std::thread::sleep(EXIT_POLL_INTERVAL);
+    }
+    !is_alive(pid)
+}
@@ -0,0 +1,271 @@
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::imageops::FilterType;
+use image::{Delay, Frame, RgbaImage};
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// Largest per-channel difference at which consecutive frames count as duplicates
+const DUPLICATE_THRESHOLD: u8 = 2;
+
+/// How often frames are halved in size to get under the size cap before giving up
+const MAX_DOWNSCALE_STEPS: u32 = 4;
+
+/// GIF quantization speed, 1 (best quality) to 30 (fastest)
+const GIF_SPEED: i32 = 20;
+
+/// Decoded pixels a recording may hold in memory, about 256 MiB of RGBA
+pub const RECORDING_PIXEL_BUDGET: u64 = 64 * 1024 * 1024;
+
+/// Largest frame kept in pixels, however few frames are recorded
+const MAX_FRAME_PIXELS: u64 = 1920 * 1080;
+
+/// Animated image format produced by `record_screen`
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingFormat {
+    /// Animated GIF, limited to 256 colours per frame
+    #[default]
+    Gif,
+    /// Animated PNG, lossless
+    Apng,
+}
+
+impl RecordingFormat {
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            RecordingFormat::Gif => "image/gif",
+            // APNG files are valid PNGs, which clients without APNG support show as a still
+            RecordingFormat::Apng => "image/png",
+        }
+    }
+
+    /// File extension used when a recording is saved
+    pub fn extension(self) -> &'static str {
+        match self {
+            RecordingFormat::Gif => "gif",
+            RecordingFormat::Apng => "png",
+        }
+    }
+}
+
+/// A frame of a recording and how long it is shown
+pub struct RecordedFrame {
+    pub image: RgbaImage,
+    pub delay_ms: u32,
+}
+
+/// Frames collected while recording, with consecutive duplicates merged
+pub struct Recording {
+    pub frames: Vec<RecordedFrame>,
+    /// Frames that were identical to the previous one
+    pub duplicates: u32,
+    /// Factor frames were downscaled by as they were recorded
+    pub scale: f64,
+    /// Largest size of a single frame, chosen so that every frame fits the pixel budget
+    max_frame_pixels: u64,
+}
+
+impl Recording {
+    /// An empty recording of at most `frame_count` frames
+    ///
+    /// Frames are downscaled as they are pushed so that `frame_count` of them stay within
+    /// `RECORDING_PIXEL_BUDGET`.
+    pub fn new(frame_count: u32) -> Self {
+        Self {
+            frames: Vec::new(),
+            duplicates: 0,
+            scale: 1.0,
+            max_frame_pixels: (RECORDING_PIXEL_BUDGET / frame_count.max(1) as u64)
+                .min(MAX_FRAME_PIXELS),
+        }
+    }
+
+    /// Decoded pixels held by the recorded frames
+    pub fn pixels(&self) -> u64 {
+        self.frames
+            .iter()
+            .map(|frame| frame.image.width() as u64 * frame.image.height() as u64)
+            .sum()
+    }
+
+    /// Append a frame, or extend the previous frame if nothing changed
+    ///
+    /// The first frame is downscaled to the per-frame pixel limit, and later frames are
+    /// scaled to the first frame's size (e.g. after a window was resized).
+    ///
+    /// # Returns
+    /// * `Ok(())` - The frame was recorded or merged into the previous one
+    /// * `Err(String)` - Error message if the frame would exceed the pixel budget
+    pub fn push(&mut self, image: RgbaImage, delay_ms: u32) -> Result<(), String> {
+        let (width, height) = match self.frames.first() {
+            Some(first) => first.image.dimensions(),
+            None => {
+                let (width, height) = image.dimensions();
+                let pixels = width as u64 * height as u64;
+                if pixels > self.max_frame_pixels {
+                    self.scale = (self.max_frame_pixels as f64 / pixels as f64).sqrt();
+                }
+                (
+                    ((width as f64 * self.scale).floor() as u32).max(1),
+                    ((height as f64 * self.scale).floor() as u32).max(1),
+                )
+            }
+        };
+        let image = if image.dimensions() == (width, height) {
+            image
+        } else {
+            image::imageops::resize(&image, width, height, FilterType::Triangle)
+        };
+
+        if let Some(last) = self.frames.last_mut() {
+            let duplicate = last.image.pixels().zip(image.pixels()).all(|(a, b)| {
+                a.0.iter()
+                    .zip(b.0.iter())
+                    .all(|(a, b)| a.abs_diff(*b) <= DUPLICATE_THRESHOLD)
+            });
+            if duplicate {
+                last.delay_ms += delay_ms;
+                self.duplicates += 1;
+                return Ok(());
+            }
+        }
+
+        if self.pixels() + width as u64 * height as u64 > RECORDING_PIXEL_BUDGET {
+            return Err(format!(
+                "Recording exceeds {} decoded pixels; record fewer frames or a smaller area",
+                RECORDING_PIXEL_BUDGET
+            ));
+        }
+        self.frames.push(RecordedFrame { image, delay_ms });
+        Ok(())
+    }
+}
+
+/// An encoded animation
+#[derive(Debug, Clone, Serialize)]
+pub struct EncodedAnimation {
+    #[serde(skip)]
+    pub data: Vec<u8>,
+    pub format: RecordingFormat,
+    pub frames: usize,
+    pub width: u32,
+    pub height: u32,
+    /// Factor the frames were resized by, while recording and to stay under the size cap
+    pub scale: f64,
+    pub bytes: usize,
+}
+
+/// Encode a recording as an animation no larger than `max_bytes`
+///
+/// If the encoded animation is too large, the frames are halved in size and encoded
+/// again, up to `MAX_DOWNSCALE_STEPS` times. Frames are borrowed, and resized one at a
+/// time when needed, so encoding holds at most one extra frame in memory.
+///
+/// # Returns
+/// * `Ok(EncodedAnimation)` - The encoded animation and the scale it was encoded at
+/// * `Err(String)` - Error message if there are no frames, encoding failed or the
+///   animation stays over the cap
+pub fn encode_animation(
+    recording: &Recording,
+    format: RecordingFormat,
+    max_bytes: usize,
+) -> Result<EncodedAnimation, String> {
+    let frames = &recording.frames;
+    let first = frames
+        .first()
+        .ok_or_else(|| "No frames were recorded".to_string())?;
+    let (full_width, full_height) = first.image.dimensions();
+
+    let mut scale = 1.0;
+    for _ in 0..=MAX_DOWNSCALE_STEPS {
+        let width = ((full_width as f64 * scale).round() as u32).max(1);
+        let height = ((full_height as f64 * scale).round() as u32).max(1);
+        let data = match format {
+            RecordingFormat::Gif => encode_gif(frames, width, height)?,
+            RecordingFormat::Apng => encode_apng(frames, width, height)?,
+        };
+        if data.len() <= max_bytes {
+            return Ok(EncodedAnimation {
+                bytes: data.len(),
+                data,
+                format,
+                frames: frames.len(),
+                width,
+                height,
+                scale: recording.scale * scale,
+            });
+        }
+        scale /= 2.0;
+    }
+
+    Err(format!(
+        "Recording exceeds {} bytes even at 1/{} size; record fewer frames or a smaller area",
+        max_bytes,
+        1 << MAX_DOWNSCALE_STEPS
+    ))
+}
+
+/// A frame at the encoding size, borrowed if it already has that size
+fn frame_at(frame: &RecordedFrame, width: u32, height: u32) -> Cow<'_, RgbaImage> {
+    if frame.image.dimensions() == (width, height) {
+        Cow::Borrowed(&frame.image)
+    } else {
+        Cow::Owned(image::imageops::resize(
+            &frame.image,
+            width,
+            height,
+            FilterType::Triangle,
+        ))
+    }
+}
+
+fn encode_gif(frames: &[RecordedFrame], width: u32, height: u32) -> Result<Vec<u8>, String> {
+    let mut buffer = Vec::new();
+    {
+        let mut encoder = GifEncoder::new_with_speed(&mut buffer, GIF_SPEED);
+        encoder
+            .set_repeat(Repeat::Infinite)
+            .map_err(|e| format!("GIF encoding failed: {}", e))?;
+        // The GIF encoder takes owned frames, so they are produced lazily, one at a time
+        let frames = frames.iter().map(|frame| {
+            Frame::from_parts(
+                frame_at(frame, width, height).into_owned(),
+                0,
+                0,
+                Delay::from_numer_denom_ms(frame.delay_ms, 1),
+            )
+        });
+        encoder
+            .encode_frames(frames)
+            .map_err(|e| format!("GIF encoding failed: {}", e))?;
+    }
+    Ok(buffer)
+}
+
+fn encode_apng(frames: &[RecordedFrame], width: u32, height: u32) -> Result<Vec<u8>, String> {
+    let error = |e: png::EncodingError| format!("APNG encoding failed: {}", e);
+
+    let mut buffer = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut buffer, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .set_animated(frames.len() as u32, 0)
+            .map_err(error)?;
+        let mut writer = encoder.write_header().map_err(error)?;
+        for frame in frames {
+            writer
+                .set_frame_delay(frame.delay_ms.min(u16::MAX as u32) as u16, 1000)
+                .map_err(error)?;
+            writer
+                .write_image_data(frame_at(frame, width, height).as_raw())
+                .map_err(error)?;
+        }
+        writer.finish().map_err(error)?;
+    }
+    Ok(buffer)
+}
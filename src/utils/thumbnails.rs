@@ -0,0 +1,210 @@
+use image::imageops::FilterType;
+use image::{Rgba, RgbaImage};
+use serde::Serialize;
+use xcap::Window;
+
+use super::drawing::{self, GLYPH_HEIGHT};
+use super::geometry::Rect;
+use super::window_ops::WindowInfo;
+
+/// Space between and around cells, in pixels
+const PADDING: u32 = 8;
+
+/// Height of the label area below each thumbnail: two lines of text and their spacing
+const LABEL_HEIGHT: u32 = 2 * GLYPH_HEIGHT + 6;
+
+const BACKGROUND: Rgba<u8> = Rgba([32, 32, 32, 255]);
+const CELL_BACKGROUND: Rgba<u8> = Rgba([48, 48, 48, 255]);
+const TEXT: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const SUBTLE_TEXT: Rgba<u8> = Rgba([180, 180, 180, 255]);
+const BADGE: Rgba<u8> = Rgba([200, 40, 40, 255]);
+
+/// Layout of a contact sheet
+#[derive(Debug, Clone, Copy)]
+pub struct SheetOptions {
+    /// Largest thumbnail width in pixels
+    pub thumbnail_width: u32,
+    /// Largest thumbnail height in pixels
+    pub thumbnail_height: u32,
+    /// Cells per row; a roughly square grid if not given
+    pub columns: Option<u32>,
+}
+
+/// One window in a contact sheet
+#[derive(Debug, Clone, Serialize)]
+pub struct SheetCell {
+    /// Number drawn on the thumbnail, counting from 0 in reading order
+    pub index: usize,
+    pub row: u32,
+    pub column: u32,
+    pub window_id: u32,
+    pub app_name: String,
+    pub title: String,
+    /// The whole cell, including its label, in contact sheet pixels
+    pub cell: Rect,
+    /// The thumbnail within the cell, in contact sheet pixels
+    pub thumbnail: Rect,
+}
+
+/// A window that could not be captured
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedWindow {
+    pub window_id: u32,
+    pub error: String,
+}
+
+/// Thumbnails of several windows laid out in a labelled grid
+pub struct ContactSheet {
+    pub image: RgbaImage,
+    pub columns: u32,
+    pub rows: u32,
+    pub cells: Vec<SheetCell>,
+    pub skipped: Vec<SkippedWindow>,
+}
+
+/// Capture windows and lay their thumbnails out in a grid
+///
+/// Each cell shows the thumbnail, scaled to fit and centered, with the cell index in its
+/// corner and the window ID, application name and title below it.
+///
+/// # Arguments
+/// * `windows` - The windows to include, in the order they should appear
+/// * `options` - Thumbnail size and number of columns
+///
+/// # Returns
+/// * `Ok(ContactSheet)` - The sheet image, one cell per captured window, and the windows
+///   that could not be captured
+/// * `Err(String)` - Error message if the window list could not be read or no window
+///   could be captured
+pub fn build_contact_sheet(
+    windows: &[WindowInfo],
+    options: SheetOptions,
+) -> Result<ContactSheet, String> {
+    let handles = Window::all().map_err(|e| format!("Failed to get window list: {}", e))?;
+
+    let mut thumbnails = Vec::new();
+    let mut skipped = Vec::new();
+    for info in windows {
+        let captured = handles
+            .iter()
+            .find(|w| w.id().unwrap_or(0) == info.id)
+            .ok_or_else(|| format!("Window ID {} does not exist", info.id))
+            .and_then(|window| {
+                window
+                    .capture_image()
+                    .map_err(|e| format!("Window screenshot failed: {}", e))
+            });
+        match captured {
+            Ok(image) => thumbnails.push((info, scale_to_fit(&image, options))),
+            Err(error) => skipped.push(SkippedWindow {
+                window_id: info.id,
+                error,
+            }),
+        }
+    }
+    if thumbnails.is_empty() {
+        return Err("No window could be captured".to_string());
+    }
+
+    let count = thumbnails.len() as u32;
+    let columns = options
+        .columns
+        .unwrap_or_else(|| (count as f64).sqrt().ceil() as u32)
+        .clamp(1, count);
+    let rows = count.div_ceil(columns);
+    let cell_width = options.thumbnail_width + PADDING;
+    let cell_height = options.thumbnail_height + LABEL_HEIGHT + PADDING;
+    let mut image = RgbaImage::from_pixel(
+        columns * (cell_width + PADDING) + PADDING,
+        rows * (cell_height + PADDING) + PADDING,
+        BACKGROUND,
+    );
+
+    let mut cells = Vec::new();
+    for (index, (info, thumbnail)) in thumbnails.into_iter().enumerate() {
+        let (row, column) = (index as u32 / columns, index as u32 % columns);
+        let cell = Rect::new(
+            (PADDING + column * (cell_width + PADDING)) as i32,
+            (PADDING + row * (cell_height + PADDING)) as i32,
+            cell_width,
+            cell_height,
+        );
+        drawing::fill_rect(&mut image, &cell, CELL_BACKGROUND);
+
+        // Center the thumbnail in the area above the label
+        let inner_x = cell.x + (PADDING / 2) as i32;
+        let inner_y = cell.y + (PADDING / 2) as i32;
+        let thumb_rect = Rect::new(
+            inner_x + ((options.thumbnail_width - thumbnail.width()) / 2) as i32,
+            inner_y + ((options.thumbnail_height - thumbnail.height()) / 2) as i32,
+            thumbnail.width(),
+            thumbnail.height(),
+        );
+        image::imageops::overlay(
+            &mut image,
+            &thumbnail,
+            thumb_rect.x as i64,
+            thumb_rect.y as i64,
+        );
+        drawing::draw_label(
+            &mut image,
+            thumb_rect.x,
+            thumb_rect.y,
+            &index.to_string(),
+            TEXT,
+            BADGE,
+            2,
+        );
+
+        let label_y = inner_y + options.thumbnail_height as i32 + 2;
+        let first_line = drawing::fit_text(
+            &format!("{} {}", info.id, info.app_name),
+            options.thumbnail_width,
+            1,
+        );
+        let second_line = drawing::fit_text(&info.title, options.thumbnail_width, 1);
+        drawing::draw_text(&mut image, inner_x, label_y, &first_line, TEXT, 1);
+        drawing::draw_text(
+            &mut image,
+            inner_x,
+            label_y + GLYPH_HEIGHT as i32 + 2,
+            &second_line,
+            SUBTLE_TEXT,
+            1,
+        );
+
+        cells.push(SheetCell {
+            index,
+            row,
+            column,
+            window_id: info.id,
+            app_name: info.app_name.clone(),
+            title: info.title.clone(),
+            cell,
+            thumbnail: thumb_rect,
+        });
+    }
+
+    Ok(ContactSheet {
+        image,
+        columns,
+        rows,
+        cells,
+        skipped,
+    })
+}
+
+/// Scale an image down to fit the thumbnail box, keeping its aspect ratio
+fn scale_to_fit(image: &RgbaImage, options: SheetOptions) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return RgbaImage::new(1, 1);
+    }
+    let factor = (options.thumbnail_width as f64 / width as f64)
+        .min(options.thumbnail_height as f64 / height as f64)
+        .min(1.0);
+    let target_width = ((width as f64 * factor).round() as u32).clamp(1, options.thumbnail_width);
+    let target_height =
+        ((height as f64 * factor).round() as u32).clamp(1, options.thumbnail_height);
+    image::imageops::resize(image, target_width, target_height, FilterType::Triangle)
+}
@@ -1,15 +1,869 @@
-use xcap::Window;
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::os::raw::c_ulong;
+use std::time::{Duration, Instant};
+use xcap::{Monitor, Window};
+
+use super::geometry::{subtract_all, Rect};
+use super::process_ops::{process_info, ProcessInfo};
 
 #[cfg(target_os = "windows")]
 use windows::Win32::Foundation::HWND;
 #[cfg(target_os = "windows")]
 use windows::Win32::UI::WindowsAndMessaging::{PostMessageW, WM_CLOSE};
 
+#[cfg(target_os = "linux")]
+use super::x11_display::XDisplay;
 #[cfg(target_os = "linux")]
 use std::ptr;
 #[cfg(target_os = "linux")]
 use x11::xlib;
 
+/// Window record returned by the window query tools
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowInfo {
+    pub id: u32,
+    pub title: String,
+    pub app_name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub is_minimized: bool,
+    pub is_maximized: bool,
+    /// Position in the stacking order, 0 being the top-most window
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub z_order: Option<usize>,
+    /// The window this one is transient for, e.g. the owner of a dialog
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transient_for: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<Visibility>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_type: Option<WindowType>,
+    /// Index of the workspace the window is on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<u32>,
+    /// Whether the window is shown on every workspace
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub on_all_workspaces: bool,
+    /// The owning process, filled in by `attach_processes`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub process: Option<ProcessInfo>,
+}
+
+/// How much of a window the user can see
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    /// Entirely on a monitor and not covered by other windows
+    OnScreen,
+    /// Partly covered by other windows or partly off all monitors
+    PartiallyVisible,
+    /// Completely covered by windows above it
+    Occluded,
+    /// Outside every monitor
+    OffScreen,
+    Minimized,
+    /// On a workspace other than the one shown
+    OtherWorkspace,
+}
+
+/// Role of a window, from `_NET_WM_WINDOW_TYPE`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowType {
+    Normal,
+    Dialog,
+    /// Panels and taskbars
+    Dock,
+    /// Desktop background windows
+    Desktop,
+    /// Toolbars, palettes and splash screens
+    Utility,
+    /// Dropdown, popup and torn-off menus, and combo box lists
+    Menu,
+    Tooltip,
+    Notification,
+}
+
+impl WindowType {
+    /// Types shown by `get_window_list` unless other types are requested
+    pub const DEFAULT_FILTER: &'static [WindowType] = &[WindowType::Normal, WindowType::Dialog];
+
+    /// Map an EWMH window type atom name to its category
+    pub fn from_atom_name(name: &str) -> Option<Self> {
+        let kind = name.strip_prefix("_NET_WM_WINDOW_TYPE_")?;
+        Some(match kind {
+            "NORMAL" => WindowType::Normal,
+            "DIALOG" => WindowType::Dialog,
+            "DOCK" => WindowType::Dock,
+            "DESKTOP" => WindowType::Desktop,
+            "UTILITY" | "TOOLBAR" | "SPLASH" => WindowType::Utility,
+            "MENU" | "DROPDOWN_MENU" | "POPUP_MENU" | "COMBO" => WindowType::Menu,
+            "TOOLTIP" => WindowType::Tooltip,
+            "NOTIFICATION" => WindowType::Notification,
+            _ => return None,
+        })
+    }
+}
+
+impl WindowInfo {
+    pub fn from_window(window: &Window) -> Self {
+        Self {
+            id: window.id().unwrap_or(0),
+            title: window.title().unwrap_or_default(),
+            app_name: window.app_name().unwrap_or_default(),
+            x: window.x().unwrap_or(0),
+            y: window.y().unwrap_or(0),
+            width: window.width().unwrap_or(0),
+            height: window.height().unwrap_or(0),
+            is_minimized: window.is_minimized().unwrap_or(false),
+            is_maximized: window.is_maximized().unwrap_or(false),
+            z_order: None,
+            transient_for: None,
+            visibility: None,
+            window_type: None,
+            workspace: None,
+            on_all_workspaces: false,
+            process: None,
+        }
+    }
+
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.x, self.y, self.width, self.height)
+    }
+
+    /// Whether any part of the window can be seen on the current workspace
+    pub fn is_visible(&self) -> bool {
+        matches!(
+            self.visibility,
+            Some(Visibility::OnScreen | Visibility::PartiallyVisible)
+        )
+    }
+}
+
+/// List all windows from the top of the stacking order down
+///
+/// Each record carries its z-order index, the window it is transient for, its window
+/// type, its workspace and its visibility, computed by subtracting the rectangles of every
+/// window above it on the current workspace and clipping to the monitors. Minimized windows
+/// and windows on other workspaces cover nothing.
+///
+/// # Platform-specific behavior
+/// - Linux: Orders windows by `_NET_CLIENT_LIST_STACKING` and reads `WM_TRANSIENT_FOR`,
+///   `_NET_WM_WINDOW_TYPE` and `_NET_WM_DESKTOP`
+/// - Other platforms: Uses the order reported by xcap; `transient_for`, `window_type`
+///   and workspaces are not reported
+pub fn list_windows() -> Result<Vec<WindowInfo>, String> {
+    let windows = Window::all().map_err(|e| format!("Failed to get window list: {}", e))?;
+    let mut infos: Vec<WindowInfo> = windows.iter().map(WindowInfo::from_window).collect();
+    let current_workspace = apply_window_manager_state(&mut infos);
+
+    // Without monitor geometry nothing can be clipped, so treat the desktop as unbounded
+    let monitors: Option<Vec<Rect>> = Monitor::all().ok().map(|monitors| {
+        monitors
+            .iter()
+            .map(|m| {
+                Rect::new(
+                    m.x().unwrap_or(0),
+                    m.y().unwrap_or(0),
+                    m.width().unwrap_or(0),
+                    m.height().unwrap_or(0),
+                )
+            })
+            .collect()
+    });
+
+    let mut above: Vec<Rect> = Vec::new();
+    for (index, info) in infos.iter_mut().enumerate() {
+        info.z_order = Some(index);
+        let rect = info.rect();
+        if info.is_minimized {
+            info.visibility = Some(Visibility::Minimized);
+            continue;
+        }
+        if info.workspace.is_some()
+            && current_workspace.is_some()
+            && info.workspace != current_workspace
+        {
+            info.visibility = Some(Visibility::OtherWorkspace);
+            continue;
+        }
+        if rect.is_empty() {
+            continue;
+        }
+
+        let on_monitors = match &monitors {
+            Some(monitors) => monitors
+                .iter()
+                .filter_map(|m| rect.intersection(m))
+                .collect(),
+            None => vec![rect],
+        };
+        let on_monitors_area: u64 = on_monitors.iter().map(Rect::area).sum();
+        let visible_area: u64 = subtract_all(on_monitors, &above)
+            .iter()
+            .map(Rect::area)
+            .sum();
+
+        info.visibility = Some(if on_monitors_area == 0 {
+            Visibility::OffScreen
+        } else if visible_area == 0 {
+            Visibility::Occluded
+        } else if visible_area == rect.area() {
+            Visibility::OnScreen
+        } else {
+            Visibility::PartiallyVisible
+        });
+        above.push(rect);
+    }
+
+    Ok(infos)
+}
+
+/// Fill in the owning process of each window
+///
+/// Windows that do not advertise a process ID are left without process details.
+#[cfg(target_os = "linux")]
+pub fn attach_processes(infos: &mut [WindowInfo]) -> Result<(), String> {
+    let display = XDisplay::open()?;
+    for info in infos.iter_mut() {
+        info.process = display
+            .long(info.id as xlib::Window, "_NET_WM_PID")
+            .filter(|pid| *pid != 0)
+            .map(|pid| process_info(pid as u32));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn attach_processes(infos: &mut [WindowInfo]) -> Result<(), String> {
+    for info in infos.iter_mut() {
+        info.process = window_pid(info.id).ok().map(process_info);
+    }
+    Ok(())
+}
+
+/// Get the full record of a single window, including its owning process
+///
+/// # Arguments
+/// * `window_id` - The ID of the window
+///
+/// # Returns
+/// * `Ok(WindowInfo)` - The window with stacking, visibility and process details
+/// * `Err(String)` - Error message if the window list is unavailable or the ID is unknown
+pub fn window_details(window_id: u32) -> Result<WindowInfo, String> {
+    let mut info = list_windows()?
+        .into_iter()
+        .find(|info| info.id == window_id)
+        .ok_or_else(|| format!("Window ID {} does not exist", window_id))?;
+
+    attach_processes(std::slice::from_mut(&mut info))?;
+    Ok(info)
+}
+
+/// Sort windows top-most first and fill in `transient_for`, `window_type` and workspaces
+///
+/// Returns the index of the current workspace, if the window manager reports one.
+#[cfg(target_os = "linux")]
+fn apply_window_manager_state(infos: &mut [WindowInfo]) -> Option<u32> {
+    let Ok(display) = XDisplay::open() else {
+        return None;
+    };
+
+    // _NET_CLIENT_LIST_STACKING runs bottom to top; unlisted windows sort last
+    if let Some(stacking) = display.longs(display.root(), "_NET_CLIENT_LIST_STACKING") {
+        infos.sort_by_key(|info| {
+            stacking
+                .iter()
+                .rposition(|id| *id == info.id as xlib::Window)
+                .map_or(usize::MAX, |position| stacking.len() - 1 - position)
+        });
+    }
+
+    for info in infos.iter_mut() {
+        info.transient_for = display
+            .long(info.id as xlib::Window, "WM_TRANSIENT_FOR")
+            .filter(|owner| *owner != 0 && *owner != display.root())
+            .map(|owner| owner as u32);
+
+        // The first recognised type wins; untyped transient windows are dialogs per EWMH
+        let declared = display
+            .longs(info.id as xlib::Window, "_NET_WM_WINDOW_TYPE")
+            .unwrap_or_default()
+            .into_iter()
+            .find_map(|atom| WindowType::from_atom_name(&display.atom_name(atom)?));
+        info.window_type = Some(declared.unwrap_or(if info.transient_for.is_some() {
+            WindowType::Dialog
+        } else {
+            WindowType::Normal
+        }));
+
+        match display.long(info.id as xlib::Window, "_NET_WM_DESKTOP") {
+            Some(ALL_WORKSPACES) => info.on_all_workspaces = true,
+            Some(index) => info.workspace = Some(index as u32),
+            None => {}
+        }
+    }
+
+    display
+        .long(display.root(), "_NET_CURRENT_DESKTOP")
+        .map(|index| index as u32)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn apply_window_manager_state(_infos: &mut [WindowInfo]) -> Option<u32> {
+    None
+}
+
+/// Fail if a window is on a workspace other than the one shown
+///
+/// Such windows are not mapped, so capturing them gives a blank or stale image.
+///
+/// # Arguments
+/// * `window_id` - The ID of the window
+///
+/// # Returns
+/// * `Ok(())` - The window is on the current workspace, on every workspace, or its
+///   workspace is unknown
+/// * `Err(String)` - Error message naming the window's workspace
+#[cfg(target_os = "linux")]
+pub fn ensure_on_current_workspace(window_id: u32) -> Result<(), String> {
+    let Ok(display) = XDisplay::open() else {
+        return Ok(());
+    };
+    let workspace = display.long(window_id as xlib::Window, "_NET_WM_DESKTOP");
+    let current = display.long(display.root(), "_NET_CURRENT_DESKTOP");
+    match (workspace, current) {
+        (Some(workspace), Some(current)) if workspace != ALL_WORKSPACES && workspace != current => {
+            Err(format!(
+                "Window ID {} is on workspace {}, not the current workspace {}; switch_workspace to it first",
+                window_id, workspace, current
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn ensure_on_current_workspace(_window_id: u32) -> Result<(), String> {
+    Ok(())
+}
+
+/// Find a window by its ID
+///
+/// # Arguments
+/// * `window_id` - The ID of the window to look up
+///
+/// # Returns
+/// * `Ok(Window)` - The matching window
+/// * `Err(String)` - Error message if the window list is unavailable or the ID is unknown
+pub fn find_window(window_id: u32) -> Result<Window, String> {
+    let windows = Window::all().map_err(|e| format!("Failed to get window list: {}", e))?;
+
+    windows
+        .into_iter()
+        .find(|w| w.id().unwrap_or(0) == window_id)
+        .ok_or_else(|| format!("Window ID {} does not exist", window_id))
+}
+
+/// Resolve the process ID that owns a window
+///
+/// # Arguments
+/// * `window_id` - The ID of the window
+///
+/// # Returns
+/// * `Ok(u32)` - The owning process ID
+/// * `Err(String)` - Error message if the window does not advertise its process or its
+///   client runs on another host
+///
+/// # Platform-specific behavior
+/// - Linux: Reads the `_NET_WM_PID` property, refusing windows whose `WM_CLIENT_MACHINE`
+///   differs from the local hostname
+/// - Other platforms: Uses the process ID reported by xcap
+#[cfg(target_os = "linux")]
+pub fn window_pid(window_id: u32) -> Result<u32, String> {
+    let display = XDisplay::open()?;
+    let window = window_id as xlib::Window;
+
+    // _NET_WM_PID is only meaningful on the machine the client runs on
+    if let Some(machine) = display.text(window, "WM_CLIENT_MACHINE") {
+        let local = local_hostname()?;
+        if !machine.eq_ignore_ascii_case(&local) {
+            return Err(format!(
+                "Window ID {} belongs to a client on host '{}', not this machine ('{}')",
+                window_id, machine, local
+            ));
+        }
+    }
+
+    display
+        .long(window, "_NET_WM_PID")
+        .map(|pid| pid as u32)
+        .filter(|pid| *pid != 0)
+        .ok_or_else(|| format!("Window ID {} does not report its process ID", window_id))
+}
+
+/// Name of this machine as clients report it in `WM_CLIENT_MACHINE`
+#[cfg(target_os = "linux")]
+fn local_hostname() -> Result<String, String> {
+    let mut buffer = [0u8; 256];
+    let result = unsafe { libc::gethostname(buffer.as_mut_ptr() as *mut _, buffer.len()) };
+    if result != 0 {
+        return Err(format!(
+            "Failed to read hostname: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    let end = buffer.iter().position(|b| *b == 0).unwrap_or(buffer.len());
+    Ok(String::from_utf8_lossy(&buffer[..end]).into_owned())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn window_pid(window_id: u32) -> Result<u32, String> {
+    find_window(window_id)?
+        .pid()
+        .map_err(|e| format!("Failed to get process ID: {}", e))
+}
+
+/// Get the window that currently has keyboard focus
+///
+/// # Returns
+/// * `Ok(Some(Window))` - The active window
+/// * `Ok(None)` - No window is active, e.g. the desktop has focus
+/// * `Err(String)` - Error message if the window list is unavailable
+///
+/// # Platform-specific behavior
+/// - Linux: Reads `_NET_ACTIVE_WINDOW` from the root window
+/// - Other platforms: Uses the focus state reported by xcap
+#[cfg(target_os = "linux")]
+pub fn active_window() -> Result<Option<Window>, String> {
+    let active_id = {
+        let display = XDisplay::open()?;
+        display.long(display.root(), "_NET_ACTIVE_WINDOW")
+    };
+
+    match active_id {
+        Some(id) if id != 0 => {
+            let windows = Window::all().map_err(|e| format!("Failed to get window list: {}", e))?;
+            Ok(windows
+                .into_iter()
+                .find(|w| w.id().unwrap_or(0) as xlib::Window == id))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn active_window() -> Result<Option<Window>, String> {
+    let windows = Window::all().map_err(|e| format!("Failed to get window list: {}", e))?;
+    Ok(windows
+        .into_iter()
+        .find(|w| w.is_focused().unwrap_or(false)))
+}
+
+/// Get the `list_windows` record of the window that currently has keyboard focus
+///
+/// The record carries the same z-order, visibility, type and workspace details as
+/// `get_window_list` reports for the window.
+///
+/// # Returns
+/// * `Ok(Some(WindowInfo))` - The active window
+/// * `Ok(None)` - No window is active
+/// * `Err(String)` - Error message if the window list is unavailable
+pub fn active_window_info() -> Result<Option<WindowInfo>, String> {
+    let Some(window) = active_window()? else {
+        return Ok(None);
+    };
+    let id = window.id().unwrap_or(0);
+    let info = list_windows()?
+        .into_iter()
+        .find(|info| info.id == id)
+        // The window may have closed in between; fall back to what xcap reported
+        .unwrap_or_else(|| WindowInfo::from_window(&window));
+    Ok(Some(info))
+}
+
+/// Get the top-most visible window whose rectangle contains a point
+///
+/// `list_windows` runs from the top of the stacking order down, so the first visible
+/// window containing the point is the one the user sees there. Minimized windows and
+/// windows on other workspaces are never hit.
+///
+/// # Arguments
+/// * `x`, `y` - The point in desktop coordinates
+pub fn window_at_point(x: i32, y: i32) -> Result<Option<WindowInfo>, String> {
+    Ok(list_windows()?
+        .into_iter()
+        .find(|info| info.is_visible() && info.rect().contains(x, y)))
+}
+
+/// Properties read by `window_properties` in addition to the requested ones
+pub const DEFAULT_WINDOW_PROPERTIES: &[&str] = &[
+    "WM_CLASS",
+    "WM_NAME",
+    "_NET_WM_NAME",
+    "_NET_WM_WINDOW_TYPE",
+    "_NET_WM_STATE",
+    "_NET_WM_DESKTOP",
+    "WM_CLIENT_MACHINE",
+];
+
+/// Decoded value of a raw window property
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum PropertyData {
+    /// Text properties, split at NUL separators (e.g. the two parts of `WM_CLASS`)
+    Text(Vec<String>),
+    /// `ATOM` properties, resolved to atom names
+    Atoms(Vec<String>),
+    /// Every other property, as unsigned integers
+    Numbers(Vec<c_ulong>),
+}
+
+/// A raw window property as `xprop` would show it
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowProperty {
+    pub name: String,
+    /// Name of the property's type atom, e.g. `UTF8_STRING` or `CARDINAL`
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub value: PropertyData,
+}
+
+/// Raw properties of a window
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowProperties {
+    pub window_id: u32,
+    pub properties: Vec<WindowProperty>,
+    /// Requested properties that are not set on the window
+    pub missing: Vec<String>,
+}
+
+/// Read raw X11 properties of a window
+///
+/// # Arguments
+/// * `window_id` - The ID of the window
+/// * `extra` - Names of properties to read in addition to `DEFAULT_WINDOW_PROPERTIES`
+///
+/// # Returns
+/// * `Ok(WindowProperties)` - The decoded properties and the names that are not set
+/// * `Err(String)` - Error message if the display cannot be opened or the window is unknown
+#[cfg(target_os = "linux")]
+pub fn window_properties(window_id: u32, extra: &[String]) -> Result<WindowProperties, String> {
+    use super::x11_display::PropertyValue;
+
+    find_window(window_id)?;
+    let display = XDisplay::open()?;
+    let window = window_id as xlib::Window;
+
+    let mut names: Vec<&str> = DEFAULT_WINDOW_PROPERTIES.to_vec();
+    for name in extra {
+        if !names.contains(&name.as_str()) {
+            names.push(name);
+        }
+    }
+
+    let text_types = ["STRING", "UTF8_STRING", "COMPOUND_TEXT", "TEXT"];
+    let mut properties = Vec::new();
+    let mut missing = Vec::new();
+    for name in names {
+        let property = display
+            .atom_if_exists(name)
+            .and_then(|atom| display.property(window, atom));
+        let Some(property) = property else {
+            missing.push(name.to_string());
+            continue;
+        };
+
+        let type_name = display.atom_name(property.type_atom).unwrap_or_default();
+        let value = match property.value {
+            PropertyValue::Bytes(bytes) if text_types.contains(&type_name.as_str()) => {
+                PropertyData::Text(
+                    bytes
+                        .split(|b| *b == 0)
+                        .filter(|part| !part.is_empty())
+                        .map(|part| String::from_utf8_lossy(part).into_owned())
+                        .collect(),
+                )
+            }
+            PropertyValue::Bytes(bytes) => {
+                PropertyData::Numbers(bytes.into_iter().map(c_ulong::from).collect())
+            }
+            PropertyValue::Shorts(shorts) => {
+                PropertyData::Numbers(shorts.into_iter().map(c_ulong::from).collect())
+            }
+            PropertyValue::Longs(longs) if type_name == "ATOM" => PropertyData::Atoms(
+                longs
+                    .into_iter()
+                    .map(|atom| display.atom_name(atom).unwrap_or_else(|| atom.to_string()))
+                    .collect(),
+            ),
+            PropertyValue::Longs(longs) => PropertyData::Numbers(longs),
+        };
+
+        properties.push(WindowProperty {
+            name: name.to_string(),
+            type_name,
+            value,
+        });
+    }
+
+    Ok(WindowProperties {
+        window_id,
+        properties,
+        missing,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn window_properties(_window_id: u32, _extra: &[String]) -> Result<WindowProperties, String> {
+    Err("X11 window properties are only available on Linux".to_string())
+}
+
+/// `_NET_WM_DESKTOP` value of windows shown on every workspace
+#[cfg(target_os = "linux")]
+const ALL_WORKSPACES: c_ulong = 0xFFFF_FFFF;
+
+/// A virtual desktop
+#[derive(Debug, Clone, Serialize)]
+pub struct Workspace {
+    pub index: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// The workspaces managed by the window manager
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceList {
+    pub count: u32,
+    /// Index of the workspace being shown
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<u32>,
+    pub workspaces: Vec<Workspace>,
+}
+
+/// List the workspaces (virtual desktops)
+///
+/// # Returns
+/// * `Ok(WorkspaceList)` - Workspace count, names and the current workspace
+/// * `Err(String)` - Error message if the window manager does not support workspaces
+///
+/// # Platform-specific behavior
+/// - Linux: Reads `_NET_NUMBER_OF_DESKTOPS`, `_NET_DESKTOP_NAMES` and `_NET_CURRENT_DESKTOP`
+/// - Other platforms: Not supported
+#[cfg(target_os = "linux")]
+pub fn list_workspaces() -> Result<WorkspaceList, String> {
+    use super::x11_display::PropertyValue;
+
+    let display = XDisplay::open()?;
+    let root = display.root();
+    let count = display
+        .long(root, "_NET_NUMBER_OF_DESKTOPS")
+        .ok_or_else(|| "The window manager does not report workspaces".to_string())?
+        as u32;
+    let current = display
+        .long(root, "_NET_CURRENT_DESKTOP")
+        .map(|index| index as u32);
+
+    // _NET_DESKTOP_NAMES is a list of NUL-terminated UTF-8 strings and may be shorter than count
+    let names: Vec<String> = match display
+        .property(root, display.atom("_NET_DESKTOP_NAMES"))
+        .map(|property| property.value)
+    {
+        Some(PropertyValue::Bytes(bytes)) => bytes
+            .split(|b| *b == 0)
+            .map(|name| String::from_utf8_lossy(name).into_owned())
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let workspaces = (0..count)
+        .map(|index| Workspace {
+            index,
+            name: names
+                .get(index as usize)
+                .filter(|name| !name.is_empty())
+                .cloned(),
+        })
+        .collect();
+
+    Ok(WorkspaceList {
+        count,
+        current,
+        workspaces,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn list_workspaces() -> Result<WorkspaceList, String> {
+    Err("Workspaces are not supported on this platform".to_string())
+}
+
+/// Switch the workspace being shown
+///
+/// # Arguments
+/// * `index` - The workspace to show
+///
+/// # Platform-specific behavior
+/// - Linux: Sends a `_NET_CURRENT_DESKTOP` client message to the window manager
+/// - Other platforms: Not supported
+#[cfg(target_os = "linux")]
+pub fn switch_workspace(index: u32) -> Result<(), String> {
+    let display = XDisplay::open()?;
+    check_workspace_index(&display, index)?;
+    display.send_client_message(
+        display.root(),
+        "_NET_CURRENT_DESKTOP",
+        [index as i64, xlib::CurrentTime as i64, 0, 0, 0],
+    )
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn switch_workspace(_index: u32) -> Result<(), String> {
+    Err("Workspaces are not supported on this platform".to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn check_workspace_index(display: &XDisplay, index: u32) -> Result<(), String> {
+    match display.long(display.root(), "_NET_NUMBER_OF_DESKTOPS") {
+        Some(count) if (index as c_ulong) < count => Ok(()),
+        Some(count) => Err(format!(
+            "Workspace {} does not exist (there are {})",
+            index, count
+        )),
+        None => Err("The window manager does not report workspaces".to_string()),
+    }
+}
+
+/// Criteria for finding a window by title and/or application name
+///
+/// Both fields are case-insensitive substring matches; a matcher with no fields set
+/// matches every window.
+#[derive(Debug, Clone, Default)]
+pub struct WindowMatcher {
+    pub title: Option<String>,
+    pub app_name: Option<String>,
+}
+
+impl WindowMatcher {
+    pub fn matches(&self, window: &Window) -> bool {
+        self.matches_text(
+            &window.title().unwrap_or_default(),
+            &window.app_name().unwrap_or_default(),
+        )
+    }
+
+    /// Same as `matches`, for a window record from `list_windows`
+    pub fn matches_info(&self, info: &WindowInfo) -> bool {
+        self.matches_text(&info.title, &info.app_name)
+    }
+
+    fn matches_text(&self, title: &str, app_name: &str) -> bool {
+        fn contains(haystack: &str, needle: &Option<String>) -> bool {
+            needle
+                .as_ref()
+                .is_none_or(|n| haystack.to_lowercase().contains(&n.to_lowercase()))
+        }
+        contains(title, &self.title) && contains(app_name, &self.app_name)
+    }
+}
+
+impl fmt::Display for WindowMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.title, &self.app_name) {
+            (Some(title), Some(app_name)) => {
+                write!(
+                    f,
+                    "with title containing '{}' and app '{}'",
+                    title, app_name
+                )
+            }
+            (Some(title), None) => write!(f, "with title containing '{}'", title),
+            (None, Some(app_name)) => write!(f, "of app '{}'", app_name),
+            (None, None) => write!(f, "of any kind"),
+        }
+    }
+}
+
+/// Interval between window list polls while waiting for a window
+pub const WINDOW_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Window condition waited for by `wait_for_window`
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowState {
+    /// A matching window exists
+    #[default]
+    Appeared,
+    /// No matching window exists
+    Disappeared,
+    /// A matching window has keyboard focus
+    Focused,
+}
+
+impl fmt::Display for WindowState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            WindowState::Appeared => "open",
+            WindowState::Disappeared => "closed",
+            WindowState::Focused => "focused",
+        })
+    }
+}
+
+/// Check whether windows matching `matcher` are in `state`
+///
+/// # Returns
+/// * `Ok((bool, Option<WindowInfo>))` - Whether the condition holds, and the matching
+///   window: the one that appeared or is focused, or for `Disappeared` the one still open
+/// * `Err(String)` - Error message if the window list is unavailable
+pub fn check_window_state(
+    matcher: &WindowMatcher,
+    state: WindowState,
+) -> Result<(bool, Option<WindowInfo>), String> {
+    match state {
+        WindowState::Appeared | WindowState::Disappeared => {
+            let found = list_windows()?
+                .into_iter()
+                .find(|info| matcher.matches_info(info));
+            Ok(((state == WindowState::Appeared) == found.is_some(), found))
+        }
+        WindowState::Focused => {
+            let focused = active_window_info()?.filter(|info| matcher.matches_info(info));
+            Ok((focused.is_some(), focused))
+        }
+    }
+}
+
+/// Wait until a window matching `matcher` exists
+///
+/// # Returns
+/// * `Ok(Window)` - The first matching window
+/// * `Err(String)` - Error message if no window matched before the timeout
+pub fn wait_for_window(matcher: &WindowMatcher, timeout: Duration) -> Result<Window, String> {
+    let start = Instant::now();
+    loop {
+        let windows = Window::all().map_err(|e| format!("Failed to get window list: {}", e))?;
+        if let Some(window) = windows.into_iter().find(|w| matcher.matches(w)) {
+            return Ok(window);
+        }
+        if start.elapsed() >= timeout {
+            return Err(format!(
+                "No window {} appeared within {} ms",
+                matcher,
+                timeout.as_millis()
+            ));
+        }
+        std::thread::sleep(WINDOW_POLL_INTERVAL);
+    }
+}
+
 /// Close a window by its ID with validation and informative message
 ///
 /// This function validates the window exists, closes it, and returns a success message
@@ -23,12 +877,7 @@ use x11::xlib;
 /// * `Err(String)` - Error message if window not found or closing failed
 pub fn close_window_with_info(window_id: u32) -> Result<String, String> {
     // Verify window exists
-    let windows = Window::all().map_err(|e| format!("Failed to get window list: {}", e))?;
-
-    let window = windows
-        .iter()
-        .find(|w| w.id().unwrap_or(0) == window_id)
-        .ok_or_else(|| format!("Window ID {} does not exist", window_id))?;
+    let window = find_window(window_id)?;
 
     let window_title = window.title().unwrap_or_default();
     let window_app = window.app_name().unwrap_or_default();
@@ -37,11 +886,340 @@ pub fn close_window_with_info(window_id: u32) -> Result<String, String> {
     close_window_by_id(window_id)?;
 
     Ok(format!(
-        "Successfully closed window: {} [{}] (ID: {})",
+        "Close request sent to window: {} [{}] (ID: {})",
         window_title, window_app, window_id
     ))
 }
 
+/// Interval between window list polls while waiting for a window to close
+const CLOSE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Observed result of a close request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseOutcome {
+    /// The window no longer exists
+    Closed,
+    /// The window still exists after the timeout expired
+    StillOpen,
+    /// A dialog for the window appeared, e.g. a "save changes?" prompt
+    NewDialogAppeared,
+}
+
+/// A dialog that appeared for the window while waiting for a close
+#[derive(Debug, Clone, Serialize)]
+pub struct DialogInfo {
+    pub id: u32,
+    pub title: String,
+}
+
+/// Report produced by [`close_window_and_wait`]
+#[derive(Debug, Clone, Serialize)]
+pub struct CloseReport {
+    pub window_id: u32,
+    pub title: String,
+    pub app_name: String,
+    pub outcome: CloseOutcome,
+    pub elapsed_ms: u64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dialogs: Vec<DialogInfo>,
+}
+
+/// Close a window and wait until the close request takes effect
+///
+/// After sending the close request the window list is re-enumerated until the window
+/// disappears, a dialog of it appears, or the timeout expires.
+///
+/// # Arguments
+/// * `window_id` - The ID of the window to close
+/// * `timeout` - Maximum time to wait for the window to disappear
+///
+/// # Returns
+/// * `Ok(CloseReport)` - The observed outcome
+/// * `Err(String)` - Error message if window not found or closing failed
+pub fn close_window_and_wait(window_id: u32, timeout: Duration) -> Result<CloseReport, String> {
+    let window = find_window(window_id)?;
+    let title = window.title().unwrap_or_default();
+    let app_name = window.app_name().unwrap_or_default();
+    let pid = window.pid().ok().filter(|pid| *pid != 0);
+    let known_ids: HashSet<u32> = Window::all()
+        .map_err(|e| format!("Failed to get window list: {}", e))?
+        .iter()
+        .filter_map(|w| w.id().ok())
+        .collect();
+
+    close_window_by_id(window_id)?;
+
+    let start = Instant::now();
+    loop {
+        std::thread::sleep(CLOSE_POLL_INTERVAL);
+
+        let windows = Window::all().map_err(|e| format!("Failed to get window list: {}", e))?;
+        let still_open = windows.iter().any(|w| w.id().unwrap_or(0) == window_id);
+
+        // Dialogs of the closed window that did not exist before the close request
+        let new_windows: Vec<&Window> = windows
+            .iter()
+            .filter(|w| w.id().is_ok_and(|id| !known_ids.contains(&id)))
+            .collect();
+        let dialogs = close_dialogs(&new_windows, window_id, pid);
+
+        let outcome = if !dialogs.is_empty() {
+            CloseOutcome::NewDialogAppeared
+        } else if !still_open {
+            CloseOutcome::Closed
+        } else if start.elapsed() >= timeout {
+            CloseOutcome::StillOpen
+        } else {
+            continue;
+        };
+
+        return Ok(CloseReport {
+            window_id,
+            title,
+            app_name,
+            outcome,
+            elapsed_ms: start.elapsed().as_millis() as u64,
+            dialogs,
+        });
+    }
+}
+
+/// Pick the new windows that are dialogs raised by closing `owner`
+///
+/// A window counts if its `WM_TRANSIENT_FOR` is the closed window, or if it belongs to
+/// the same process and declares `_NET_WM_WINDOW_TYPE_DIALOG`.
+#[cfg(target_os = "linux")]
+fn close_dialogs(windows: &[&Window], owner: u32, pid: Option<u32>) -> Vec<DialogInfo> {
+    if windows.is_empty() {
+        return Vec::new();
+    }
+    let Ok(display) = XDisplay::open() else {
+        return Vec::new();
+    };
+
+    windows
+        .iter()
+        .filter(|w| {
+            let id = w.id().unwrap_or(0) as xlib::Window;
+            let transient_for_owner =
+                display.long(id, "WM_TRANSIENT_FOR") == Some(owner as c_ulong);
+            let dialog_of_process = pid.is_some()
+                && w.pid().ok() == pid
+                && display
+                    .longs(id, "_NET_WM_WINDOW_TYPE")
+                    .unwrap_or_default()
+                    .into_iter()
+                    .any(|atom| {
+                        display.atom_name(atom).as_deref() == Some("_NET_WM_WINDOW_TYPE_DIALOG")
+                    });
+            transient_for_owner || dialog_of_process
+        })
+        .map(|w| DialogInfo {
+            id: w.id().unwrap_or(0),
+            title: w.title().unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Without window manager hints, every new window of the same process counts
+#[cfg(not(target_os = "linux"))]
+fn close_dialogs(windows: &[&Window], _owner: u32, pid: Option<u32>) -> Vec<DialogInfo> {
+    windows
+        .iter()
+        .filter(|w| pid.is_some() && w.pid().ok() == pid)
+        .map(|w| DialogInfo {
+            id: w.id().unwrap_or(0),
+            title: w.title().unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Window management operations supported by [`manage_window`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowAction {
+    /// Activate the window and raise it above other windows
+    Focus,
+    /// Move and/or resize the window; fields left as `None` keep their current value
+    MoveResize {
+        x: Option<i32>,
+        y: Option<i32>,
+        width: Option<u32>,
+        height: Option<u32>,
+    },
+    Minimize,
+    Maximize,
+    /// Leave the maximized, fullscreen or minimized state
+    Restore,
+    Fullscreen(bool),
+    /// Move the window to a workspace by index
+    MoveToWorkspace(u32),
+}
+
+impl WindowAction {
+    fn past_tense(&self) -> String {
+        let text = match self {
+            WindowAction::Focus => "Focused",
+            WindowAction::MoveResize { .. } => "Moved/resized",
+            WindowAction::Minimize => "Minimized",
+            WindowAction::Maximize => "Maximized",
+            WindowAction::Restore => "Restored",
+            WindowAction::Fullscreen(true) => "Entered fullscreen for",
+            WindowAction::Fullscreen(false) => "Left fullscreen for",
+            WindowAction::MoveToWorkspace(index) => {
+                return format!("Assigned workspace {} to", index);
+            }
+        };
+        text.to_string()
+    }
+}
+
+/// Apply a window management action with validation and informative message
+///
+/// # Arguments
+/// * `window_id` - The ID of the window to manage
+/// * `action` - The action to apply
+///
+/// # Returns
+/// * `Ok(String)` - Message with window details
+/// * `Err(String)` - Error message if window not found or the action failed
+pub fn manage_window_with_info(window_id: u32, action: WindowAction) -> Result<String, String> {
+    let window = find_window(window_id)?;
+
+    manage_window(window_id, action)?;
+
+    Ok(format!(
+        "{} window: {} [{}] (ID: {})",
+        action.past_tense(),
+        window.title().unwrap_or_default(),
+        window.app_name().unwrap_or_default(),
+        window_id
+    ))
+}
+
+/// Apply a window management action (platform-specific implementation)
+///
+/// # Platform-specific behavior
+/// - Linux: Sends EWMH client messages (`_NET_ACTIVE_WINDOW`, `_NET_MOVERESIZE_WINDOW`,
+///   `_NET_WM_STATE`, `_NET_WM_DESKTOP`) to the window manager; minimizing uses
+///   `XIconifyWindow`
+///
+/// The window manager applies the request asynchronously and may refuse it.
+#[cfg(target_os = "linux")]
+pub fn manage_window(window_id: u32, action: WindowAction) -> Result<(), String> {
+    // EWMH source indication: 2 = request from a pager or other direct user action
+    const SOURCE_PAGER: i64 = 2;
+    const STATE_REMOVE: i64 = 0;
+    const STATE_ADD: i64 = 1;
+
+    let display = XDisplay::open()?;
+    let window = window_id as xlib::Window;
+    let set_state = |operation: i64, first: &str, second: Option<&str>| {
+        display.send_client_message(
+            window,
+            "_NET_WM_STATE",
+            [
+                operation,
+                display.atom(first) as i64,
+                second.map(|name| display.atom(name) as i64).unwrap_or(0),
+                SOURCE_PAGER,
+                0,
+            ],
+        )
+    };
+    let activate = || {
+        display.send_client_message(
+            window,
+            "_NET_ACTIVE_WINDOW",
+            [SOURCE_PAGER, xlib::CurrentTime as i64, 0, 0, 0],
+        )?;
+        let (_, error) = display.trap_errors(|| unsafe {
+            xlib::XRaiseWindow(display.raw(), window);
+        });
+        if error.is_some() {
+            return Err("Failed to raise window".to_string());
+        }
+        Ok::<(), String>(())
+    };
+
+    match action {
+        WindowAction::Focus => activate(),
+        WindowAction::MoveResize {
+            x,
+            y,
+            width,
+            height,
+        } => {
+            // Bits 8-11 flag which of x, y, width, height are present; bits 12-15 hold the source
+            let mut flags = SOURCE_PAGER << 12;
+            for (bit, present) in [x.is_some(), y.is_some(), width.is_some(), height.is_some()]
+                .into_iter()
+                .enumerate()
+            {
+                if present {
+                    flags |= 1 << (8 + bit);
+                }
+            }
+            if flags & 0xf00 == 0 {
+                return Err("At least one of x, y, width or height is required".to_string());
+            }
+            display.send_client_message(
+                window,
+                "_NET_MOVERESIZE_WINDOW",
+                [
+                    flags,
+                    x.unwrap_or(0) as i64,
+                    y.unwrap_or(0) as i64,
+                    width.unwrap_or(0) as i64,
+                    height.unwrap_or(0) as i64,
+                ],
+            )
+        }
+        WindowAction::Minimize => {
+            let (result, error) = display.trap_errors(|| unsafe {
+                xlib::XIconifyWindow(display.raw(), window, xlib::XDefaultScreen(display.raw()))
+            });
+            if result == 0 || error.is_some() {
+                return Err("Failed to minimize window".to_string());
+            }
+            Ok(())
+        }
+        WindowAction::Maximize => set_state(
+            STATE_ADD,
+            "_NET_WM_STATE_MAXIMIZED_VERT",
+            Some("_NET_WM_STATE_MAXIMIZED_HORZ"),
+        ),
+        WindowAction::Restore => {
+            set_state(STATE_REMOVE, "_NET_WM_STATE_FULLSCREEN", None)?;
+            set_state(
+                STATE_REMOVE,
+                "_NET_WM_STATE_MAXIMIZED_VERT",
+                Some("_NET_WM_STATE_MAXIMIZED_HORZ"),
+            )?;
+            // Activating a minimized window maps it again
+            activate()
+        }
+        WindowAction::Fullscreen(enabled) => set_state(
+            if enabled { STATE_ADD } else { STATE_REMOVE },
+            "_NET_WM_STATE_FULLSCREEN",
+            None,
+        ),
+        WindowAction::MoveToWorkspace(index) => {
+            check_workspace_index(&display, index)?;
+            display.send_client_message(
+                window,
+                "_NET_WM_DESKTOP",
+                [index as i64, SOURCE_PAGER, 0, 0, 0],
+            )
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn manage_window(_window_id: u32, _action: WindowAction) -> Result<(), String> {
+    Err("Window management is not supported on this platform".to_string())
+}
+
 /// Close a window by its ID (platform-specific implementation)
 ///
 /// # Arguments
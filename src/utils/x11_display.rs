@@ -0,0 +1,294 @@
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_int, c_long, c_uchar, c_ulong};
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, AtomicU8, Ordering};
+use std::sync::Mutex;
+use x11::xlib;
+
+/// Raw value of an X11 window property, decoded according to its format
+#[derive(Debug, Clone)]
+pub enum PropertyValue {
+    /// Format 8 data (strings, byte arrays)
+    Bytes(Vec<u8>),
+    /// Format 16 data
+    Shorts(Vec<u16>),
+    /// Format 32 data (cardinals, atoms, window IDs)
+    Longs(Vec<c_ulong>),
+}
+
+/// An X11 window property together with its type atom
+#[derive(Debug, Clone)]
+pub struct Property {
+    pub type_atom: xlib::Atom,
+    pub value: PropertyValue,
+}
+
+/// Owned connection to the X server, closed on drop
+///
+/// Xlib's default error handler terminates the process on errors such as `BadWindow`,
+/// which are routine when a window disappears between enumeration and use. Requests that
+/// target windows which may be gone run inside [`XDisplay::trap_errors`] instead.
+pub struct XDisplay {
+    raw: *mut xlib::Display,
+}
+
+/// Upper bound on property length requested from the server, in 32-bit units
+const MAX_PROPERTY_LENGTH: c_long = 1 << 24;
+
+/// Xlib error handler callback
+type ErrorHandler =
+    Option<unsafe extern "C" fn(*mut xlib::Display, *mut xlib::XErrorEvent) -> c_int>;
+
+/// Serialises error traps, since Xlib has a single process-wide error handler
+static TRAP_LOCK: Mutex<()> = Mutex::new(());
+
+/// Display whose errors the active trap collects
+static TRAPPED_DISPLAY: AtomicPtr<xlib::Display> = AtomicPtr::new(ptr::null_mut());
+
+/// First error code seen by the active trap, 0 if none
+static TRAPPED_ERROR: AtomicU8 = AtomicU8::new(0);
+
+/// Handler that was installed before the active trap, for errors on other connections
+static PREVIOUS_HANDLER: Mutex<ErrorHandler> = Mutex::new(None);
+
+unsafe extern "C" fn trap_x_error(
+    display: *mut xlib::Display,
+    event: *mut xlib::XErrorEvent,
+) -> c_int {
+    if display == TRAPPED_DISPLAY.load(Ordering::SeqCst) {
+        let _ = TRAPPED_ERROR.compare_exchange(
+            0,
+            (*event).error_code,
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+        );
+        return 0;
+    }
+    let previous = *PREVIOUS_HANDLER.lock().unwrap_or_else(|e| e.into_inner());
+    match previous {
+        Some(handler) => handler(display, event),
+        None => 0,
+    }
+}
+
+impl XDisplay {
+    /// Open the display named by `$DISPLAY`
+    pub fn open() -> Result<Self, String> {
+        let raw = unsafe { xlib::XOpenDisplay(ptr::null()) };
+        if raw.is_null() {
+            return Err("Failed to open X11 display".to_string());
+        }
+        Ok(Self { raw })
+    }
+
+    /// The underlying Xlib display pointer
+    pub fn raw(&self) -> *mut xlib::Display {
+        self.raw
+    }
+
+    /// Run requests with X errors on this connection collected instead of fatal
+    ///
+    /// The error handler is only replaced for the duration of the call, and the
+    /// connection is synced before it is restored so that errors from asynchronous
+    /// requests are caught too. Errors on other connections still reach the previous
+    /// handler. `requests` must not start another trap.
+    ///
+    /// # Returns
+    /// The value returned by `requests` and the code of the first error it caused, if any
+    pub fn trap_errors<T>(&self, requests: impl FnOnce() -> T) -> (T, Option<u8>) {
+        let _guard = TRAP_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        TRAPPED_ERROR.store(0, Ordering::SeqCst);
+        TRAPPED_DISPLAY.store(self.raw, Ordering::SeqCst);
+        unsafe {
+            let previous = xlib::XSetErrorHandler(Some(trap_x_error));
+            *PREVIOUS_HANDLER.lock().unwrap_or_else(|e| e.into_inner()) = previous;
+        }
+
+        let result = requests();
+
+        unsafe {
+            xlib::XSync(self.raw, xlib::False);
+            let previous = *PREVIOUS_HANDLER.lock().unwrap_or_else(|e| e.into_inner());
+            xlib::XSetErrorHandler(previous);
+        }
+        TRAPPED_DISPLAY.store(ptr::null_mut(), Ordering::SeqCst);
+        let error = TRAPPED_ERROR.load(Ordering::SeqCst);
+        (result, (error != 0).then_some(error))
+    }
+
+    /// The root window of the default screen
+    pub fn root(&self) -> xlib::Window {
+        unsafe { xlib::XDefaultRootWindow(self.raw) }
+    }
+
+    /// Intern an atom by name
+    pub fn atom(&self, name: &str) -> xlib::Atom {
+        let name = CString::new(name).unwrap_or_default();
+        unsafe { xlib::XInternAtom(self.raw, name.as_ptr(), xlib::False) }
+    }
+
+    /// Look up an atom by name without creating it
+    ///
+    /// Returns `None` if no client has interned the name, in which case no window can have
+    /// a property of that name. Use this for names supplied by clients, since interned
+    /// atoms live as long as the X server.
+    pub fn atom_if_exists(&self, name: &str) -> Option<xlib::Atom> {
+        let name = CString::new(name).ok()?;
+        let atom = unsafe { xlib::XInternAtom(self.raw, name.as_ptr(), xlib::True) };
+        (atom != 0).then_some(atom)
+    }
+
+    /// Look up the name of an atom
+    pub fn atom_name(&self, atom: xlib::Atom) -> Option<String> {
+        if atom == 0 {
+            return None;
+        }
+        let (name, error) = self.trap_errors(|| unsafe { xlib::XGetAtomName(self.raw, atom) });
+        if name.is_null() {
+            return None;
+        }
+        if error.is_some() {
+            unsafe { xlib::XFree(name as *mut _) };
+            return None;
+        }
+        unsafe {
+            let result = CStr::from_ptr(name).to_string_lossy().into_owned();
+            xlib::XFree(name as *mut _);
+            Some(result)
+        }
+    }
+
+    /// Read a property of any type from a window
+    pub fn property(&self, window: xlib::Window, property: xlib::Atom) -> Option<Property> {
+        let mut type_atom: xlib::Atom = 0;
+        let mut format: c_int = 0;
+        let mut item_count: c_ulong = 0;
+        let mut bytes_after: c_ulong = 0;
+        let mut data: *mut c_uchar = ptr::null_mut();
+
+        // BadWindow is expected when the window was destroyed after it was listed
+        let (status, error) = self.trap_errors(|| unsafe {
+            xlib::XGetWindowProperty(
+                self.raw,
+                window,
+                property,
+                0,
+                MAX_PROPERTY_LENGTH,
+                xlib::False,
+                xlib::AnyPropertyType as xlib::Atom,
+                &mut type_atom,
+                &mut format,
+                &mut item_count,
+                &mut bytes_after,
+                &mut data,
+            )
+        });
+        if error.is_some() || status != xlib::Success as c_int || data.is_null() {
+            if !data.is_null() {
+                unsafe { xlib::XFree(data as *mut _) };
+            }
+            return None;
+        }
+
+        // Xlib returns format 32 items as C longs and format 16 items as C shorts
+        let count = item_count as usize;
+        let value = unsafe {
+            match format {
+                8 => Some(PropertyValue::Bytes(
+                    std::slice::from_raw_parts(data, count).to_vec(),
+                )),
+                16 => Some(PropertyValue::Shorts(
+                    std::slice::from_raw_parts(data as *const u16, count).to_vec(),
+                )),
+                32 => Some(PropertyValue::Longs(
+                    std::slice::from_raw_parts(data as *const c_ulong, count).to_vec(),
+                )),
+                _ => None,
+            }
+        };
+        unsafe {
+            xlib::XFree(data as *mut _);
+        }
+
+        value.map(|value| Property { type_atom, value })
+    }
+
+    /// Read a format 32 property (CARDINAL, ATOM, WINDOW) as a list of values
+    pub fn longs(&self, window: xlib::Window, name: &str) -> Option<Vec<c_ulong>> {
+        match self.property(window, self.atom(name))?.value {
+            PropertyValue::Longs(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Read the first value of a format 32 property
+    pub fn long(&self, window: xlib::Window, name: &str) -> Option<c_ulong> {
+        self.longs(window, name)?.first().copied()
+    }
+
+    /// Read a text property (`STRING` or `UTF8_STRING`)
+    pub fn text(&self, window: xlib::Window, name: &str) -> Option<String> {
+        match self.property(window, self.atom(name))?.value {
+            PropertyValue::Bytes(bytes) => Some(
+                String::from_utf8_lossy(&bytes)
+                    .trim_end_matches('\0')
+                    .to_string(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Send an EWMH client message to the root window on behalf of `window`
+    pub fn send_client_message(
+        &self,
+        window: xlib::Window,
+        message_type: &str,
+        data: [i64; 5],
+    ) -> Result<(), String> {
+        let mut event: xlib::XClientMessageEvent = unsafe { std::mem::zeroed() };
+        event.type_ = xlib::ClientMessage;
+        event.window = window;
+        event.message_type = self.atom(message_type);
+        event.format = 32;
+        for (slot, value) in event.data.as_longs_mut().iter_mut().zip(data) {
+            *slot = value as _;
+        }
+
+        let root = self.root();
+        let (result, error) = self.trap_errors(|| unsafe {
+            xlib::XSendEvent(
+                self.raw,
+                root,
+                xlib::False,
+                xlib::SubstructureRedirectMask | xlib::SubstructureNotifyMask,
+                &mut event as *mut xlib::XClientMessageEvent as *mut xlib::XEvent,
+            )
+        });
+        if result == 0 || error.is_some() {
+            return Err(format!("Failed to send {} message", message_type));
+        }
+        Ok(())
+    }
+
+    /// Flush the output buffer
+    pub fn flush(&self) {
+        unsafe {
+            xlib::XFlush(self.raw);
+        }
+    }
+
+    /// Flush the output buffer and wait until the server has processed every request
+    pub fn sync(&self) {
+        unsafe {
+            xlib::XSync(self.raw, xlib::False);
+        }
+    }
+}
+
+impl Drop for XDisplay {
+    fn drop(&mut self) {
+        unsafe {
+            xlib::XCloseDisplay(self.raw);
+        }
+    }
+}
@@ -218,3 +218,39 @@ def test_monitor_capture_with_index():
 
         data = base64.b64decode(screenshot)
         assert data[:8] == b"\x89PNG\r\n\x1a\n"
+
+
+def test_run_server_options():
+    """Test that run_server exposes the server options"""
+    import window_cap_mcp as wc
+    import inspect
+
+    params = inspect.signature(wc.run_server).parameters
+    for name in [
+        "confirm",
+        "elicitation_fallback",
+        "allow_kill",
+        "allow_input",
+        "capture_history",
+        "capture_history_mb",
+        "output_dir",
+        "filename_template",
+        "write_roots",
+        "image_delivery",
+    ]:
+        assert name in params, f"run_server should accept {name}"
+
+
+def test_run_server_invalid_options():
+    """Test that invalid option values are rejected before the server starts"""
+    import window_cap_mcp as wc
+    import pytest
+
+    with pytest.raises(ValueError, match="confirm policy"):
+        wc.run_server(confirm="sometimes")
+
+    with pytest.raises(ValueError, match="elicitation fallback"):
+        wc.run_server(elicitation_fallback="maybe")
+
+    with pytest.raises(ValueError, match="image delivery"):
+        wc.run_server(image_delivery="carrier_pigeon")